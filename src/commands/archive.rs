@@ -0,0 +1,261 @@
+//! This module contains the logic for the archive and unarchive commands
+
+use std::{fs, io};
+
+use colored::Colorize;
+
+use crate::{
+    config::WorkflowsConfig,
+    intergrations::{
+        self,
+        fzf::{select_option, ProjectFilter},
+    },
+    repo::Repo,
+    state::archive,
+};
+
+use super::{
+    confirm,
+    delete::{print_check, remove_checked_project, ProjectCheck, Removal},
+    format_age, get_local_projects,
+};
+
+/// Archives a local project, packing it into the archive directory and removing its checkout.
+///
+/// If the user passes in a project name that is valid, fzf is not launched.
+///
+/// # Parameters
+///
+/// - `project_name` The passed in project from a parameter
+/// - `config` The user's config
+pub fn archive_command(project_name: Option<String>, config: WorkflowsConfig) -> io::Result<()> {
+    let repo = match project_name {
+        Some(project_name) => {
+            let local_projects = get_local_projects(config.general().projects_dirs());
+
+            match local_projects
+                .into_iter()
+                .find(|x| x.name() == project_name)
+            {
+                Some(repo) => repo,
+                None => {
+                    eprintln!("No project named {} could be found!", project_name.bold());
+                    return Ok(());
+                }
+            }
+        }
+        None => match intergrations::fzf::run_fzf(
+            "Archive: ",
+            true,
+            false,
+            &ProjectFilter::Checkouts,
+            &config,
+        ) {
+            Some(repo) => repo,
+            None => return Ok(()),
+        },
+    };
+
+    let project_root = repo.get_project_root().expect("Failed to get project root");
+    println!(
+        "Archiving project located at {}\n",
+        project_root.display().to_string().bold()
+    );
+
+    let check = ProjectCheck::run(&repo, &config);
+    print_check(&check, &config);
+
+    let prompt = match check.has_session() {
+        true => format!("Archive {} and kill its tmux session?", repo.name()),
+        false => format!("Archive {}?", repo.name()),
+    };
+    if !confirm(prompt, false) {
+        return Ok(());
+    }
+
+    remove_checked_project(&check, Removal::Archive, &config)?;
+
+    Ok(())
+}
+
+/// Packs a project into the archive directory, then removes its checkout and tmuxinator config
+///
+/// # Parameters
+///
+/// - `repo` The project to archive
+/// - `config` The user's config
+pub(super) fn archive_local_project(repo: &Repo, config: &WorkflowsConfig) -> io::Result<()> {
+    let archive_dir = config
+        .archive()
+        .dir()
+        .ok_or_else(|| io::Error::other("couldn't find a directory for the archive"))?;
+    let project_root = repo.get_project_root().expect("Failed to get project root");
+
+    println!("Archiving {} to {}", repo.name(), archive_dir.display());
+
+    let archived = archive::archive_project(
+        &archive_dir,
+        &repo.name(),
+        &repo.project_dir().unwrap_or_default(),
+        &project_root,
+        &config.archive().excludes(),
+    )?;
+
+    intergrations::tmuxinator::delete_tmuxinator(repo)?;
+    fs::remove_dir_all(&project_root)?;
+
+    println!(
+        "Archived {} to {}! Restore it with {}",
+        repo.name(),
+        archived.archive().display(),
+        format!("workflows unarchive {}", repo.name()).bold()
+    );
+
+    Ok(())
+}
+
+/// Unpacks an archived project back into the projects directory it was in. With `--list`, the
+/// archived projects are listed instead. A flag is used so a project named `list` can still be
+/// unarchived, as project names can't start with `-`.
+///
+/// If the user passes in a project name that is valid, fzf is not launched.
+///
+/// # Parameters
+///
+/// - `project_name` The passed in project from a parameter
+/// - `config` The user's config
+pub fn unarchive_command(project_name: Option<String>, config: WorkflowsConfig) -> io::Result<()> {
+    if matches!(project_name.as_deref(), Some("--list" | "-l")) {
+        return list_archive(&config);
+    }
+
+    unarchive(project_name, &config)?;
+
+    Ok(())
+}
+
+/// Unpacks the most recently archived project with the given name, or the one the user selects
+///
+/// # Parameters
+///
+/// - `project_name` The name of the project to unarchive
+/// - `config` The user's config
+///
+/// # Returns
+///
+/// The unarchived project, `None` if nothing was unarchived
+pub fn unarchive(
+    project_name: Option<String>,
+    config: &WorkflowsConfig,
+) -> io::Result<Option<Repo>> {
+    let projects = match config.archive().dir() {
+        Some(archive_dir) => archive::list(&archive_dir),
+        None => vec![],
+    };
+
+    if projects.is_empty() {
+        println!("The archive is empty");
+        return Ok(None);
+    }
+
+    let project_name = match project_name {
+        Some(project_name) => project_name,
+        None => {
+            let mut names: Vec<String> = vec![];
+            for project in &projects {
+                if !names.iter().any(|name| name == project.name()) {
+                    names.push(project.name().to_string());
+                }
+            }
+
+            match select_option("Unarchive: ", &names, config) {
+                Some(project_name) => project_name,
+                None => return Ok(None),
+            }
+        }
+    };
+
+    let project = match projects.iter().find(|x| x.name() == project_name) {
+        Some(project) => project,
+        None => {
+            eprintln!("No project named {} is archived!", project_name.bold());
+            return Ok(None);
+        }
+    };
+
+    match archive::unarchive_project(project) {
+        Ok(()) => println!(
+            "Restored {} to {}",
+            project.name(),
+            project.original_path().display()
+        ),
+        Err(err) if err.kind() == io::ErrorKind::AlreadyExists => {
+            eprintln!(
+                "{} couldn't unarchive {}, as {}",
+                "ERROR".bright_red(),
+                project.name().bold(),
+                err
+            );
+            return Ok(None);
+        }
+        Err(err) => return Err(err),
+    }
+
+    if !project.excludes().is_empty() {
+        println!(
+            "{}",
+            format!(
+                "{} were left out of the archive, and may need rebuilding",
+                project.excludes().join(", ")
+            )
+            .dimmed()
+        );
+    }
+
+    Ok(Some(Repo::new(
+        project.name(),
+        true,
+        Some(project.project_dir()),
+    )))
+}
+
+/// Lists the archived projects
+///
+/// # Parameters
+///
+/// - `config` The user's config
+fn list_archive(config: &WorkflowsConfig) -> io::Result<()> {
+    let projects = match config.archive().dir() {
+        Some(archive_dir) => archive::list(&archive_dir),
+        None => vec![],
+    };
+
+    if projects.is_empty() {
+        println!("The archive is empty");
+        return Ok(());
+    }
+
+    let width = projects
+        .iter()
+        .map(|project| project.name().len())
+        .max()
+        .unwrap_or_default();
+
+    for project in projects {
+        let size = fs::metadata(project.archive())?.len();
+
+        println!(
+            "• {:width$}  {}  {}",
+            project.name(),
+            project.original_path().display(),
+            format!(
+                "archived {}, {:.1} MB",
+                format_age(project.archived_at().elapsed().unwrap_or_default()),
+                size as f64 / 1_000_000.0
+            )
+            .dimmed()
+        );
+    }
+
+    Ok(())
+}
@@ -3,11 +3,14 @@ use std::io;
 use colored::Colorize;
 
 use crate::{
-    config::WorkflowsConfig,
-    intergrations::{self, fzf::get_project_dir},
+    config::{hooks::Hook, WorkflowsConfig},
+    intergrations::{
+        self,
+        fzf::{get_project_dir, ProjectFilter},
+    },
 };
 
-use super::delete;
+use super::{clone_github_project, delete, hooks::run_hook, launch_project};
 
 /// Clones a user-selected project from github, and prompts to delete it after the session ends
 ///
@@ -23,22 +26,53 @@ pub fn borrow_project(config: WorkflowsConfig) -> io::Result<()> {
         return Ok(());
     }
 
-    let selected_project =
-        intergrations::fzf::run_fzf(&config.fzf().open_prompt(), false, true, &config);
+    let selected_project = intergrations::fzf::run_fzf(
+        &config.fzf().open_prompt(),
+        false,
+        true,
+        &ProjectFilter::All,
+        &config,
+    );
 
     if let Some(mut selected_project) = selected_project {
-        match get_project_dir(&config) {
-            Some(project_dir) => {
-                intergrations::gh::clone_repo(&selected_project, project_dir.clone())?;
-                selected_project.set_project_dir(Some(project_dir));
-            }
+        if !run_hook(Hook::PreBorrow, &selected_project, None, &config)? {
+            return Ok(());
+        }
+
+        let project_dir = match get_project_dir(&config) {
+            Some(project_dir) => project_dir,
             None => return Ok(()),
+        };
+
+        let open = clone_github_project(&mut selected_project, project_dir, &config)?;
+
+        // A borrowed project is only kept while it's open, so a clone whose setup failed is
+        // still offered for deletion
+        let cloned = selected_project
+            .get_project_root()
+            .is_some_and(|project_root| project_root.is_dir());
+        if !cloned {
+            return Ok(());
         }
 
-        intergrations::tmuxinator::run_tmuxinator(&selected_project, config.tmuxinator())?;
+        if open {
+            launch_project(&selected_project, &config)?;
+        }
+
+        // Kept for the post_borrow hook, as the remote can't be looked up once the project is gone
+        let remote_url = selected_project
+            .get_project_root()
+            .and_then(|project_root| intergrations::git::remote_url(&project_root, "origin"));
 
         println!();
-        delete::delete_local_project(&selected_project, true, config)?;
+        if delete::delete_local_project(&selected_project, true, config.clone())? {
+            run_hook(
+                Hook::PostBorrow,
+                &selected_project,
+                remote_url.as_deref(),
+                &config,
+            )?;
+        }
     }
 
     Ok(())
@@ -1,12 +1,84 @@
-use crate::config::WorkflowsConfig;
+use std::io;
+
+use colored::Colorize;
+
+use crate::config::{hooks::Hook, WorkflowsConfig};
 use crate::{intergrations, repo::Repo};
 
+use super::{hooks::run_hook, setup_cloned_project};
+
 /// Attempts to clone the git repo at the given url into the user's project folder
-pub fn git_clone(url: Option<String>, config: &WorkflowsConfig) -> Option<Repo> {
-    let project_dir = intergrations::git::clone_repo(&url.clone()?, config).ok()?;
+///
+/// # Returns
+///
+/// The cloned project, if it was cloned and should be opened
+pub fn git_clone(url: Option<String>, config: &WorkflowsConfig) -> io::Result<Option<Repo>> {
+    let url = match url {
+        Some(url) => url,
+        None => return Ok(None),
+    };
 
     // Parsing the url
-    let project_name = url?.split('/').last()?.replace(".git", "");
+    let project_name = match url.split('/').next_back() {
+        Some(project_name) => project_name.replace(".git", ""),
+        None => return Ok(None),
+    };
+
+    let mut repo = Repo::new(project_name, true, None);
+
+    if !run_hook(Hook::PreClone, &repo, Some(&url), config)? {
+        return Ok(None);
+    }
+
+    let project_dir = match intergrations::git::clone_repo(&url, config) {
+        Ok(project_dir) => project_dir,
+        Err(err) => {
+            eprintln!("{} {}", "ERROR".bright_red(), err);
+            return Ok(None);
+        }
+    };
+    repo.set_project_dir(Some(project_dir));
+
+    run_hook(Hook::PostClone, &repo, Some(&url), config)?;
+
+    Ok(setup_cloned_project(&repo, config)?.then_some(repo))
+}
+
+/// Clones one of the user's GitHub projects into the given projects directory, running the
+/// user's clone hooks and setup rules
+///
+/// # Parameters
+///
+/// - `repo` The project to clone, which has its projects directory set once cloned
+/// - `projects_dir` The projects directory to clone the project into
+/// - `config` The user's config
+///
+/// # Returns
+///
+/// Whether the project should be opened
+pub fn clone_github_project(
+    repo: &mut Repo,
+    projects_dir: String,
+    config: &WorkflowsConfig,
+) -> io::Result<bool> {
+    // The url is only looked up when needed, as it's a request to GitHub
+    let remote_url = config
+        .hooks()
+        .command(Hook::PreClone)
+        .or(config.hooks().command(Hook::PostClone))
+        .and_then(|_| intergrations::gh::clone_url(repo));
+
+    if !run_hook(Hook::PreClone, repo, remote_url.as_deref(), config)? {
+        return Ok(false);
+    }
+
+    if let Err(err) = intergrations::gh::clone_repo(repo, projects_dir.clone()) {
+        eprintln!("{} {}", "ERROR".bright_red(), err);
+        return Ok(false);
+    }
+    repo.set_project_dir(Some(projects_dir));
+
+    run_hook(Hook::PostClone, repo, remote_url.as_deref(), config)?;
 
-    Some(Repo::new(project_name, true, Some(project_dir)))
+    setup_cloned_project(repo, config)
 }
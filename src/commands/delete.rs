@@ -2,14 +2,27 @@
 
 use std::fs;
 use std::io::{self, stdout, Write};
+use std::path::Path;
+use std::time::Duration;
 
-use colored::Colorize;
+use colored::{ColoredString, Colorize};
 
-use crate::config::WorkflowsConfig;
-use crate::intergrations::{self, git::PushedResult};
+use crate::config::{hooks::Hook, WorkflowsConfig};
+use crate::intergrations::{
+    self,
+    git::{FetchResult, SafetyReport},
+};
 use crate::repo::Repo;
+use crate::state::{backups, trash};
 
-use super::get_local_projects;
+use super::{
+    archive::archive_local_project, confirm, format_age, get_local_projects,
+    history::forget_history, hooks::run_hook, map_in_parallel, tags::forget_tags,
+    trash::purge_expired,
+};
+
+/// The most stash entries, untracked files or precious files listed before the rest are counted
+const MAX_LISTED_ENTRIES: usize = 5;
 
 /// Runs fzf with only local projects, and deletes the selected one.
 ///
@@ -31,17 +44,17 @@ pub fn delete_project(project: Option<String>, config: WorkflowsConfig) -> io::R
                 eprintln!("No project named {} could be found!", project.bold());
                 Ok(())
             }
-            Some(repo) => delete_local_project(repo, false, config),
+            Some(repo) => delete_local_project(repo, false, config).map(|_| ()),
         };
     }
 
-    let project = intergrations::fzf::run_fzf(&config.fzf().delete_prompt(), true, false, &config);
+    let projects = intergrations::fzf::run_fzf_multi(&config.fzf().delete_prompt(), &config);
 
-    if let Some(project) = project {
-        delete_local_project(&project, false, config)?;
+    match projects.as_slice() {
+        [] => Ok(()),
+        [project] => delete_local_project(project, false, config).map(|_| ()),
+        projects => delete_local_projects(projects, &config),
     }
-
-    Ok(())
 }
 
 /// Deletes a project from ~/Projects/
@@ -51,82 +64,713 @@ pub fn delete_project(project: Option<String>, config: WorkflowsConfig) -> io::R
 /// - `repo`        The project to delete
 /// - `default_yes` Whether the default option should be yes
 /// - `config`      The user's config
+///
+/// # Returns
+///
+/// Whether the project was removed
 pub fn delete_local_project(
     repo: &Repo,
     default_yes: bool,
     config: WorkflowsConfig,
-) -> io::Result<()> {
+) -> io::Result<bool> {
     let binding = repo.get_project_root().expect("Failed to get project root");
     let project_root = binding.to_str().expect("Failed to get str");
 
+    if !run_hook(Hook::PreDelete, repo, None, &config)? {
+        return Ok(false);
+    }
+
     println!("Deleting project located at {}\n", project_root.bold());
 
-    if config.git().check_push() {
-        // Checking if the project has been pushed
-        print!("[{}] main pushed...", "~".bright_yellow());
-        stdout().flush()?;
-        // Output the result depending on the status of the function return
+    let mut report = None;
+    if config.git().check_push() || config.git().check_tree() {
+        match intergrations::git::is_repo(&binding) {
+            true => report = check_repo(repo, &config)?,
+            false => println!(
+                "[{}] not a git repo, so nothing could be checked\n",
+                check_mark(false)
+            ),
+        }
+    }
+
+    let session = intergrations::tmux::session_commands(&repo.name())?;
+    let prompt = match &session {
+        Some(commands) => {
+            print_session(commands);
+            format!("Delete {} and kill its tmux session?", repo.name())
+        }
+        None => format!("Delete {}?", repo.name()),
+    };
+
+    if !confirm(prompt, default_yes) {
+        return Ok(false);
+    }
+
+    remove_project(
+        repo,
+        report.as_ref(),
+        session.is_some(),
+        Removal::preferred(&config),
+        &config,
+    )
+}
+
+/// Deletes several projects at once. The checks are run on every project in parallel, and a
+/// summary of them is shown before the user confirms the deletion of all, or each, of them
+///
+/// # Parameters
+///
+/// - `repos`  The projects to delete
+/// - `config` The user's config
+fn delete_local_projects(repos: &[Repo], config: &WorkflowsConfig) -> io::Result<()> {
+    print!(
+        "[{}] checking {} projects...",
+        "~".bright_yellow(),
+        repos.len()
+    );
+    stdout().flush()?;
+
+    let checks = map_in_parallel(repos, |repo| ProjectCheck::run(repo, config));
+
+    println!(
+        "\r[{}] checked {} projects   \n",
+        check_mark(true),
+        repos.len()
+    );
+    print_summary(&checks, config);
+
+    let confirmation = casual::prompt(format!("Delete all {} projects?", checks.len()))
+        .suffix(" [y]es, [N]o or [e]ach: ")
+        .default("n".to_string())
+        .matches(|s| {
+            matches!(
+                &*s.trim().to_lowercase(),
+                "y" | "yes" | "n" | "no" | "e" | "each"
+            )
+        })
+        .get();
+
+    let confirm_each = match &*confirmation.trim().to_lowercase() {
+        "y" | "yes" => false,
+        "e" | "each" => true,
+        _ => return Ok(()),
+    };
+
+    for check in &checks {
+        if confirm_each {
+            println!("\n{}", check.repo.name().bold());
+            print_check(check, config);
+
+            if !confirm(format!("Delete {}?", check.repo.name()), false) {
+                continue;
+            }
+        }
+
+        println!();
+        remove_checked_project(check, Removal::preferred(config), config)?;
+    }
+
+    Ok(())
+}
+
+/// Removes a project that has already been checked, running its `pre_delete` hook first
+///
+/// # Parameters
+///
+/// - `check`   The checks of the project to remove
+/// - `removal` How the project is removed
+/// - `config`  The user's config
+///
+/// # Returns
+///
+/// Whether the project was removed
+pub(super) fn remove_checked_project(
+    check: &ProjectCheck,
+    removal: Removal,
+    config: &WorkflowsConfig,
+) -> io::Result<bool> {
+    if !run_hook(Hook::PreDelete, check.repo, None, config)? {
+        return Ok(false);
+    }
+
+    remove_project(
+        check.repo,
+        check.report.as_ref(),
+        check.session.is_some(),
+        removal,
+        config,
+    )
+}
+
+/// How a project is removed once the user has confirmed its deletion
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Removal {
+    /// Moved to the trash, so it can be restored
+    Trash,
+    /// Deleted straight away
+    Delete,
+    /// Packed into the archive directory
+    Archive,
+}
+
+impl Removal {
+    /// How the user has chosen for projects to be removed, which is the trash unless it's
+    /// disabled
+    ///
+    /// # Parameters
+    ///
+    /// - `config` The user's config
+    pub(super) fn preferred(config: &WorkflowsConfig) -> Self {
+        match config.trash().enabled() {
+            true => Removal::Trash,
+            false => Removal::Delete,
+        }
+    }
+}
+
+/// The outcome of checking a project before deleting it
+pub(super) struct ProjectCheck<'a> {
+    /// The project that was checked
+    repo: &'a Repo,
+    /// Whether the project is a git repo
+    is_repo: bool,
+    /// The safety report, `None` if the checks are disabled or failed
+    report: Option<SafetyReport>,
+    /// The command running in each pane of the project's tmux session, if it has one
+    session: Option<Vec<String>>,
+}
+
+/// Prints what was found when checking a project, along with its tmux session
+///
+/// # Parameters
+///
+/// - `check`  The checks of the project
+/// - `config` The user's config
+pub(super) fn print_check(check: &ProjectCheck, config: &WorkflowsConfig) {
+    match &check.report {
+        Some(report) => print_safety_report(report, config),
+        None if !check.is_repo && (config.git().check_push() || config.git().check_tree()) => {
+            println!(
+                "[{}] not a git repo, so nothing could be checked\n",
+                check_mark(false)
+            )
+        }
+        None => {}
+    }
+    if let Some(commands) = &check.session {
+        print_session(commands);
+    }
+}
+
+impl<'a> ProjectCheck<'a> {
+    /// Runs the checks on a project without printing anything, so it can be run alongside the
+    /// checks of other projects
+    ///
+    /// # Parameters
+    ///
+    /// - `repo`   The project to check
+    /// - `config` The user's config
+    pub(super) fn run(repo: &'a Repo, config: &WorkflowsConfig) -> Self {
+        let project_root = repo.get_project_root().expect("Failed to get project root");
+        let is_repo = intergrations::git::is_repo(&project_root);
+        let checks_enabled = config.git().check_push() || config.git().check_tree();
+
+        let report = match is_repo && checks_enabled {
+            true => {
+                if config.git().check_push() && config.git().fetch_before_check() {
+                    let timeout = Duration::from_secs(config.git().fetch_timeout());
+                    // The last fetch is used when this fails, which the report says
+                    let _ = intergrations::git::fetch(&project_root, timeout);
+                }
+
+                intergrations::git::safety_report(&project_root, &config.git().precious_files())
+                    .ok()
+            }
+            false => None,
+        };
+
+        ProjectCheck {
+            repo,
+            is_repo,
+            report,
+            session: intergrations::tmux::session_commands(&repo.name()).unwrap_or_default(),
+        }
+    }
+
+    /// The project that was checked
+    pub(super) fn repo(&self) -> &Repo {
+        self.repo
+    }
+
+    /// Whether the project has a tmux session, which is killed when it's removed
+    pub(super) fn has_session(&self) -> bool {
+        self.session.is_some()
+    }
+
+    /// Whether the project was checked, and is fully pushed and clean
+    ///
+    /// # Parameters
+    ///
+    /// - `config` The user's config
+    pub(super) fn safe(&self, config: &WorkflowsConfig) -> bool {
+        self.report
+            .as_ref()
+            .is_some_and(|report| !has_unsaved_work(report, config))
+    }
+}
+
+/// Whether the enabled checks found unpushed commits or a dirty tree in a repo
+///
+/// # Parameters
+///
+/// - `report` The repo's safety report
+/// - `config` The user's config
+fn has_unsaved_work(report: &SafetyReport, config: &WorkflowsConfig) -> bool {
+    (config.git().check_push() && report.unpushed())
+        || (config.git().check_tree() && report.dirty())
+}
+
+/// Prints a table summarising the checks of several projects
+///
+/// # Parameters
+///
+/// - `checks` The checks to summarise
+/// - `config` The user's config
+fn print_summary(checks: &[ProjectCheck], config: &WorkflowsConfig) {
+    let width = checks
+        .iter()
+        .map(|check| check.repo.name().len())
+        .max()
+        .unwrap_or_default();
+
+    for check in checks {
+        let (status, details) = summarise(check, config);
+
         println!(
-            "\r{}\n",
-            match intergrations::git::repo_pushed(repo)? {
-                PushedResult::Status(status) => format!(
-                    "[{}] main pushed   ",
-                    match status {
-                        false => "⨯".bright_red().bold(),
-                        true => "✓".bright_green().bold(),
+            "{:width$}  {}  {}",
+            check.repo.name(),
+            status,
+            details.join(", ").dimmed()
+        );
+    }
+    println!();
+}
+
+/// Summarises the checks of a project
+///
+/// # Parameters
+///
+/// - `check`  The checks to summarise
+/// - `config` The user's config
+///
+/// # Returns
+///
+/// The status of the project, and what was found
+pub(super) fn summarise(
+    check: &ProjectCheck,
+    config: &WorkflowsConfig,
+) -> (ColoredString, Vec<String>) {
+    let mut details = vec![];
+
+    let status = match (&check.report, check.is_repo) {
+        (None, false) if config.git().check_push() || config.git().check_tree() => {
+            details.push("not a git repo, so nothing could be checked".to_string());
+            format!("{:15}", "unchecked").bright_yellow()
+        }
+        (None, true) if config.git().check_push() || config.git().check_tree() => {
+            details.push("the checks failed".to_string());
+            format!("{:15}", "unchecked").bright_yellow()
+        }
+        (None, _) => format!("{:15}", "unchecked").dimmed(),
+        (Some(report), _) => {
+            let unpushed = config.git().check_push() && report.unpushed();
+            let dirty = config.git().check_tree() && report.dirty();
+
+            if unpushed {
+                let count = report
+                    .branches()
+                    .iter()
+                    .filter(|branch| !branch.pushed())
+                    .count();
+                details.push(count_of(count, "unpushed branch", "unpushed branches"));
+            }
+            if config.git().check_tree() {
+                for (count, singular, plural) in [
+                    (
+                        report.status().changed().len(),
+                        "uncommitted change",
+                        "uncommitted changes",
+                    ),
+                    (
+                        report.status().untracked().len(),
+                        "untracked file",
+                        "untracked files",
+                    ),
+                    (report.stashes().len(), "stash entry", "stash entries"),
+                    (
+                        report.precious().len(),
+                        "precious ignored file",
+                        "precious ignored files",
+                    ),
+                    (
+                        report.operations().len(),
+                        "operation in progress",
+                        "operations in progress",
+                    ),
+                ] {
+                    if count > 0 {
+                        details.push(count_of(count, singular, plural));
                     }
-                ),
-                PushedResult::NoConnection => format!(
-                    "{}, cannot get push status",
-                    "No Connection".bright_red().bold()
-                ),
+                }
             }
+
+            match (unpushed, dirty) {
+                (true, true) => format!("{:15}", "unpushed, dirty").bright_red(),
+                (true, false) => format!("{:15}", "unpushed").bright_red(),
+                (false, true) => format!("{:15}", "dirty").bright_yellow(),
+                (false, false) => format!("{:15}", "clean").bright_green(),
+            }
+        }
+    };
+
+    if let Some(commands) = &check.session {
+        let busy = commands
+            .iter()
+            .filter(|command| !intergrations::tmux::is_shell(command))
+            .count();
+        details.push(format!(
+            "tmux session with {}",
+            count_of(busy, "busy pane", "busy panes")
+        ));
+    }
+
+    (status, details)
+}
+
+/// Formats a count with the singular or plural label to match, e.g. `1 untracked file`
+fn count_of(count: usize, singular: &str, plural: &str) -> String {
+    format!("{} {}", count, if count == 1 { singular } else { plural })
+}
+
+/// Removes a project the user has confirmed the deletion of, backing it up first if needed
+///
+/// # Parameters
+///
+/// - `repo`        The project to remove
+/// - `report`      The project's safety report, if it was checked
+/// - `has_session` Whether the project has a tmux session to kill
+/// - `removal`     How the project is removed
+/// - `config`      The user's config
+///
+/// # Returns
+///
+/// Whether the project was removed, as the user can stop it if the backup fails
+fn remove_project(
+    repo: &Repo,
+    report: Option<&SafetyReport>,
+    has_session: bool,
+    removal: Removal,
+    config: &WorkflowsConfig,
+) -> io::Result<bool> {
+    let binding = repo.get_project_root().expect("Failed to get project root");
+    let project_root = binding.to_str().expect("Failed to get str");
+
+    // Kept for the post_delete hook, as the remote can't be looked up once the project is gone
+    let remote_url = intergrations::git::remote_url(&binding, "origin").unwrap_or_default();
+
+    // Precious files are ignored, so they're backed up alongside any unsaved work
+    let precious = report.map(|report| report.precious()).unwrap_or_default();
+    let needs_backup =
+        !precious.is_empty() || report.is_some_and(|report| has_unsaved_work(report, config));
+
+    // Archives keep the whole repo, so there's nothing to back up
+    if removal != Removal::Archive
+        && needs_backup
+        && config.git().backup_unpushed()
+        && !backup_repo(repo, &binding, precious)
+    {
+        return Ok(false);
+    }
+
+    match removal {
+        Removal::Trash => {
+            let trash_dir = trash::trash_dir()
+                .ok_or_else(|| io::Error::other("couldn't find a directory for the trash"))?;
+
+            println!("Moving {} to the trash", repo.name());
+            trash::move_to_trash(
+                &trash_dir,
+                repo,
+                &binding,
+                &intergrations::tmuxinator::tmuxinator_config_file(repo),
+            )?;
+
+            println!(
+                "Moved {} to the trash! Restore it with {}",
+                repo.name(),
+                format!("workflows trash restore {}", repo.name()).bold()
+            );
+
+            purge_expired(&trash_dir, config)?;
+        }
+        Removal::Archive => archive_local_project(repo, config)?,
+        Removal::Delete => {
+            println!("Deleting tmuxinator config");
+            intergrations::tmuxinator::delete_tmuxinator(repo)?;
+            println!("Deleting project located at {}", project_root);
+            delete_project_dir(repo)?;
+            // Only dropped once the project is gone for good, as trashed and archived projects
+            // can be brought back
+            forget_tags(repo);
+            forget_history(repo);
+
+            println!("Deleted {}!", repo.name());
+        }
+    }
+
+    run_hook(Hook::PostDelete, repo, Some(&remote_url), config)?;
+
+    // Killed last, as workflows may be running inside the session itself
+    if has_session && !intergrations::tmux::kill_session(&repo.name())? {
+        eprintln!(
+            "{} couldn't kill the {} tmux session",
+            "WARNING".bright_yellow(),
+            repo.name().bold()
         );
     }
 
-    if config.git().check_tree() {
-        // Checking if the project has a clean work tree
-        print!("[{}] clean working tree...", "~".bright_yellow().bold());
+    Ok(true)
+}
+
+/// Checks whether anything would be lost by deleting a repo, printing what was found
+///
+/// # Parameters
+///
+/// - `repo` The repo to check
+/// - `config` The user's config
+///
+/// # Returns
+///
+/// The safety report that was printed, `None` if the repo couldn't be checked
+fn check_repo(repo: &Repo, config: &WorkflowsConfig) -> io::Result<Option<SafetyReport>> {
+    let project_root = repo.get_project_root().expect("Failed to get project root");
+
+    if config.git().check_push() && config.git().fetch_before_check() {
+        let timeout = Duration::from_secs(config.git().fetch_timeout());
+
+        print!("[{}] fetching remotes...", "~".bright_yellow());
         stdout().flush()?;
         println!(
-            "\r[{}] clean working tree   \n",
-            match intergrations::git::repo_clean_tree(repo)? {
-                false => "⨯".bright_red().bold(),
-                true => "✓".bright_green().bold(),
+            "\r{}\n",
+            match intergrations::git::fetch(&project_root, timeout)? {
+                FetchResult::Fetched => format!("[{}] fetched remotes   ", check_mark(true)),
+                FetchResult::Failed => format!(
+                    "[{}] couldn't fetch remotes, using the last fetch",
+                    check_mark(false)
+                ),
+                FetchResult::TimedOut => format!(
+                    "[{}] fetching remotes timed out after {}s, using the last fetch",
+                    check_mark(false),
+                    timeout.as_secs()
+                ),
             }
         );
     }
 
-    if config.git().check_push() || config.git().check_tree() {
-        // Only displaying the check message if checks have been made
+    match intergrations::git::safety_report(&project_root, &config.git().precious_files()) {
+        Ok(report) => {
+            print_safety_report(&report, config);
+            Ok(Some(report))
+        }
+        Err(err) => {
+            eprintln!("{} {}\n", "WARNING".bright_yellow(), err);
+            Ok(None)
+        }
+    }
+}
+
+/// Backs up a repo's refs, uncommitted changes and precious files, printing how to restore them
+///
+/// # Parameters
+///
+/// - `repo` The repo to back up
+/// - `project_root` The root of the repo
+/// - `precious` The precious ignored files found by the safety report
+///
+/// # Returns
+///
+/// Whether the deletion should go ahead. If the backup fails, the user is asked
+fn backup_repo(repo: &Repo, project_root: &Path, precious: &[String]) -> bool {
+    let backup = backups::backups_dir()
+        .ok_or_else(|| io::Error::other("couldn't find a directory for backups"))
+        .and_then(|backups_dir| {
+            backups::create_backup(&backups_dir, &repo.name(), project_root, precious)
+        });
+
+    let backup = match backup {
+        Ok(backup) => backup,
+        Err(err) => {
+            eprintln!(
+                "{} couldn't back up {}, as {}",
+                "ERROR".bright_red(),
+                repo.name().bold(),
+                err
+            );
+            return confirm("Delete anyway?", false);
+        }
+    };
+
+    println!(
+        "Backed up unpushed work to {}",
+        backup.dir().display().to_string().bold()
+    );
+    if let Some(bundle) = backup.bundle() {
+        println!(
+            "    Restore the repo with {}",
+            format!("git clone {} {}", bundle.display(), repo.name()).bold()
+        );
+    }
+    if let Some(patch) = backup.patch() {
+        // Without any commits there's no bundle to clone, so the repo has to be made first
+        let init = match backup.bundle() {
+            Some(_) => String::new(),
+            None => format!("git init {} && ", repo.name()),
+        };
+        println!(
+            "    Re-apply the uncommitted changes with {}",
+            format!("{}git -C {} apply {}", init, repo.name(), patch.display()).bold()
+        );
+    }
+    if let Some(precious) = backup.precious() {
         println!(
-            "{}: These checks are only for the main branch of the repo\n",
-            "NOTE".bright_red().bold()
+            "    Restore the precious files with {}",
+            format!("cp -a {}/. {}", precious.display(), repo.name()).bold()
         );
     }
+    println!();
 
-    if !casual::prompt(format!("Delete {}?", repo.name()))
-        .suffix(match default_yes {
-            true => " [Y/n] ",
-            false => " [y/N] ",
-        })
-        .default(match default_yes {
-            true => "y".to_string(),
-            false => "n".to_string(),
-        })
-        .matches(|s| matches!(&*s.trim().to_lowercase(), "n" | "no" | "y" | "yes"))
-        .map(|s| matches!(&*s.to_lowercase(), "y" | "yes"))
-    {
-        return Ok(());
+    true
+}
+
+/// Prints the parts of a safety report the user has enabled checks for
+///
+/// # Parameters
+///
+/// - `report` The report to print
+/// - `config` The user's config
+fn print_safety_report(report: &SafetyReport, config: &WorkflowsConfig) {
+    if config.git().check_push() {
+        let width = report
+            .branches()
+            .iter()
+            .map(|branch| branch.name().len())
+            .max()
+            .unwrap_or_default();
+
+        for branch in report.branches() {
+            let upstream = match (branch.upstream(), branch.gone()) {
+                (None, _) => "no upstream".bright_red().to_string(),
+                (Some(upstream), true) => format!("{} {}", upstream, "gone".bright_red()),
+                (Some(upstream), false) => {
+                    format!("{} ↑{} ↓{}", upstream, branch.ahead(), branch.behind())
+                }
+            };
+
+            println!(
+                "[{}] {:width$}  {}",
+                check_mark(branch.pushed()),
+                branch.name(),
+                upstream
+            );
+        }
+
+        let last_fetched = match report.last_fetched() {
+            Some(last_fetched) => format!(
+                "Compared against remote-tracking refs fetched {}",
+                format_age(last_fetched.elapsed().unwrap_or_default())
+            ),
+            None => "Compared against remote-tracking refs that haven't been fetched since cloning"
+                .to_string(),
+        };
+        println!("{}\n", last_fetched.dimmed());
     }
 
-    println!("Deleting tmuxinator config");
-    intergrations::tmuxinator::delete_tmuxinator(repo)?;
-    println!("Deleting project located at {}", project_root);
-    delete_project_dir(repo)?;
+    if config.git().check_tree() {
+        if report.status().head().is_none() {
+            println!(
+                "[{}] HEAD is detached, commits made on it may not be on any branch",
+                check_mark(false)
+            );
+        }
+        print_entries("uncommitted changes", report.status().changed());
+        print_entries("untracked files", report.status().untracked());
+        print_entries("stash entries", report.stashes());
+        print_entries("precious ignored files", report.precious());
 
-    println!("Deleted {}!", repo.name());
-    Ok(())
+        match report.operations().is_empty() {
+            true => println!("[{}] no operations in progress", check_mark(true)),
+            false => report.operations().iter().for_each(|operation| {
+                println!("[{}] {} in progress", check_mark(false), operation.name())
+            }),
+        }
+        println!();
+    }
+}
+
+/// Prints the processes that would be stopped by killing a project's tmux session
+///
+/// # Parameters
+///
+/// - `commands` The command running in each pane of the session
+pub(super) fn print_session(commands: &[String]) {
+    let busy: Vec<String> = commands
+        .iter()
+        .filter(|command| !intergrations::tmux::is_shell(command))
+        .cloned()
+        .collect();
+
+    println!(
+        "[{}] tmux session is running with {} panes",
+        check_mark(false),
+        commands.len()
+    );
+    print_entries("panes running something other than a shell", &busy);
+    println!();
+}
+
+/// Prints how many of something would be lost by deleting a project, followed by the first few
+///
+/// # Parameters
+///
+/// - `label` What is being counted, e.g. `stash entries`
+/// - `entries` The entries to print
+fn print_entries(label: &str, entries: &[String]) {
+    println!(
+        "[{}] {} {}",
+        check_mark(entries.is_empty()),
+        entries.len(),
+        label
+    );
+
+    entries
+        .iter()
+        .take(MAX_LISTED_ENTRIES)
+        .for_each(|entry| println!("    {}", entry.dimmed()));
+
+    if entries.len() > MAX_LISTED_ENTRIES {
+        println!(
+            "    {}",
+            format!("and {} more", entries.len() - MAX_LISTED_ENTRIES).dimmed()
+        );
+    }
+}
+
+/// The mark shown next to a check, depending on whether it passed
+fn check_mark(passed: bool) -> ColoredString {
+    match passed {
+        true => "✓".bright_green().bold(),
+        false => "⨯".bright_red().bold(),
+    }
 }
 
 /// Deletes a project from ~/Projects/
@@ -140,3 +784,96 @@ fn delete_project_dir(project: &Repo) -> io::Result<()> {
         None => Ok(()),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::Path};
+
+    use crate::{
+        config::WorkflowsConfig,
+        repo::Repo,
+        test_utils::{git, TempDir},
+    };
+
+    use super::{summarise, ProjectCheck};
+
+    /// Checks a project, returning its status without colours and the details of the summary
+    fn summary(projects_dir: &Path, name: &str, config: &WorkflowsConfig) -> (String, Vec<String>) {
+        let repo = Repo::new(
+            name.to_string(),
+            true,
+            Some(projects_dir.display().to_string()),
+        );
+
+        let (status, details) = summarise(&ProjectCheck::run(&repo, config), config);
+
+        (status.trim().to_string(), details)
+    }
+
+    #[test]
+    fn summarise_works() {
+        let projects_dir = TempDir::new("summarise");
+        let git = |dir: &str, args: &[&str]| git(&projects_dir.join(dir), args);
+        let commit = |dir: &str, file: &str| {
+            fs::write(projects_dir.join(dir).join(file), "").unwrap();
+            git(dir, &["add", "-A"]);
+            git(dir, &["commit", "--quiet", "-m", file]);
+        };
+
+        git(
+            ".",
+            &[
+                "init",
+                "--quiet",
+                "--bare",
+                "--initial-branch=main",
+                "remote",
+            ],
+        );
+        git(".", &["clone", "--quiet", "remote", "clean"]);
+        commit("clean", "README.md");
+        git("clean", &["push", "--quiet", "-u", "origin", "main"]);
+
+        git(".", &["clone", "--quiet", "remote", "unpushed"]);
+        commit("unpushed", "feature.rs");
+
+        git(".", &["clone", "--quiet", "remote", "dirty"]);
+        fs::write(projects_dir.join("dirty/notes.md"), "").unwrap();
+        fs::write(projects_dir.join("dirty/todo.md"), "").unwrap();
+
+        fs::create_dir_all(projects_dir.join("plain")).unwrap();
+
+        let config = WorkflowsConfig::default();
+
+        assert_eq!(
+            summary(&projects_dir, "clean", &config),
+            ("clean".to_string(), vec![])
+        );
+        assert_eq!(
+            summary(&projects_dir, "unpushed", &config),
+            (
+                "unpushed".to_string(),
+                vec!["1 unpushed branch".to_string()]
+            )
+        );
+        assert_eq!(
+            summary(&projects_dir, "dirty", &config),
+            ("dirty".to_string(), vec!["2 untracked files".to_string()])
+        );
+        assert_eq!(
+            summary(&projects_dir, "plain", &config),
+            (
+                "unchecked".to_string(),
+                vec!["not a git repo, so nothing could be checked".to_string()]
+            )
+        );
+
+        // With the checks disabled, nothing is reported
+        let config: WorkflowsConfig =
+            toml::from_str("[git]\ncheck_push = false\ncheck_tree = false").unwrap();
+        assert_eq!(
+            summary(&projects_dir, "unpushed", &config),
+            ("unchecked".to_string(), vec![])
+        );
+    }
+}
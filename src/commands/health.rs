@@ -2,24 +2,49 @@
 
 use std::io::{self, stdout, Write};
 
-use colored::Colorize;
+use colored::{ColoredString, Colorize};
 
-/// Checks if the required programs are available on path
+/// The programs workflows can't run without
+const REQUIRED_DEPENDENCIES: [&str; 5] = ["fzf", "gh", "git", "tmux", "tmuxinator"];
+
+/// The programs only needed for some features, along with what they're needed for
+const OPTIONAL_DEPENDENCIES: [(&str, &str); 2] = [
+    ("tar", "archiving projects"),
+    ("zstd", "archiving projects"),
+];
+
+/// Checks if the required programs are available on path, and which of the optional ones are
 pub fn health_check() -> io::Result<()> {
-    let dependencies = ["fzf", "gh", "git", "tmux", "tmuxinator"];
-
-    for dependency in dependencies {
-        print!("[{}] {}", "~".bright_yellow(), dependency);
-        stdout().flush()?;
-        let path = which::which(dependency);
-        println!(
-            "\r[{}]",
-            match path {
-                Ok(_) => "✓".bright_green().bold(),
-                Err(_) => "⨯".bright_red().bold(),
-            }
-        );
+    for dependency in REQUIRED_DEPENDENCIES {
+        check_dependency(dependency, "⨯".bright_red().bold(), "")?;
+    }
+
+    for (dependency, purpose) in OPTIONAL_DEPENDENCIES {
+        let note = format!(" (optional, needed for {})", purpose);
+        check_dependency(dependency, "-".bright_yellow().bold(), &note)?;
     }
 
     Ok(())
 }
+
+/// Prints whether a program is available on path
+///
+/// # Parameters
+///
+/// - `dependency` The program to look for
+/// - `missing`    The symbol shown if the program can't be found
+/// - `note`       Text shown after the program's name
+fn check_dependency(dependency: &str, missing: ColoredString, note: &str) -> io::Result<()> {
+    print!("[{}] {}{}", "~".bright_yellow(), dependency, note.dimmed());
+    stdout().flush()?;
+    let path = which::which(dependency);
+    println!(
+        "\r[{}]",
+        match path {
+            Ok(_) => "✓".bright_green().bold(),
+            Err(_) => missing,
+        }
+    );
+
+    Ok(())
+}
@@ -6,17 +6,54 @@ workflows v{}
 
 A cli tool for creating a seemless workflow with remote and local git repos.
 
-To open a project, run workflows with no arguments.
+To open a project, run workflows with no arguments. Pass #tag to only pick from projects with that tag.
 
 Commands
   --new       -n   Creates a new project
   --open      -o   Opens a local project
+  --last           Reopens the most recently opened project
+  -                Switches to the project opened before the current one
   --clone     -c   Clones the git repo from the given URL and opens it using workflows
   --borrow    -b   Clones a github project, prompting deletion after the session is closed
-  --delete    -d   Deletes the given project from the local machine
-  --list      -l   Shows all local projects grouped under the parent dir. Optional param for filtering
+  --delete    -d   Deletes the given project, or the projects selected with tab, from the local machine
+  --favourites -f  Opens the picker with only favourite projects
+  --list      -l   Shows all local projects grouped under the parent dir and tag. Optional param, or #tag, for filtering
   --health         Checks that workflows can access the required programs
   --help      -h   Show this dialog
+  --no-setup       Skips the [[on_clone]] setup commands after cloning a project
+
+New Options
+  --template  -t   The template to create the project with, skipping the template prompt. Use none for no template
+  --dir       -D   The projects directory to create the project in, skipping the directory prompt
+
+Template Commands
+  template list          Lists the configured templates
+  template show <name>   Shows a template's steps, merged with the templates it extends
+  template test <name>   Runs a template in a throwaway directory, reporting how each step went
+
+Trash Commands
+  trash list             Lists the deleted projects in the trash
+  trash restore <name>   Restores a deleted project to where it was
+  trash purge [name]     Permanently deletes a project in the trash, or every project
+
+Archive Commands
+  archive [name]         Packs a project into the archive, removing its checkout
+  unarchive [name]       Unpacks an archived project back to where it was
+  unarchive --list  -l   Lists the archived projects
+
+Tag Commands
+  tag add <name> <tag>     Tags a project
+  tag remove <name> <tag>  Removes a tag from a project
+  tag list                 Lists every tag with its projects
+  fav [name]               Adds or removes a favourite project, or lists them
+
+History Commands
+  history list           Lists opened projects, by frecency
+  history reset          Forgets every opened project
+
+Prune Commands
+  prune                  Suggests projects untouched for 90 days to trash, delete or archive
+  prune --older-than -O  How long projects have to be untouched, e.g. 30d, 12w or 1y
 ",
         env!("CARGO_PKG_VERSION")
     );
@@ -0,0 +1,114 @@
+//! This module contains the logic for the history command
+
+use std::{fs, io, time::SystemTime};
+
+use colored::Colorize;
+
+use crate::{
+    config::WorkflowsConfig,
+    repo::Repo,
+    state::history::{self, History},
+};
+
+use super::{confirm, format_age, get_local_projects, update_state};
+
+/// Runs a history subcommand
+///
+/// # Parameters
+///
+/// - `subcommand` The subcommand to run, either `list` or `reset`
+/// - `config` The user's config
+pub fn history_command(subcommand: Option<String>, config: WorkflowsConfig) -> io::Result<()> {
+    let history_file = match history::history_file() {
+        Some(history_file) => history_file,
+        None => {
+            println!("There is no history");
+            return Ok(());
+        }
+    };
+
+    match subcommand.as_deref() {
+        Some("list") => {
+            // Projects removed without workflows are left out
+            let history = match History::load(&history_file) {
+                Ok(history) => {
+                    history.retain_existing(&get_local_projects(config.general().projects_dirs()))
+                }
+                Err(err) => {
+                    eprintln!("{} {}", "ERROR".bright_red(), err);
+                    return Ok(());
+                }
+            };
+            let now = SystemTime::now();
+
+            let mut entries = history.entries();
+            entries.sort_by(|a, b| b.frecency(now).total_cmp(&a.frecency(now)));
+
+            if entries.is_empty() {
+                println!("No projects have been opened yet");
+                return Ok(());
+            }
+
+            let width = entries
+                .iter()
+                .map(|entry| entry.name().len())
+                .max()
+                .unwrap_or_default();
+
+            for entry in entries {
+                println!(
+                    "• {:width$}  {}  {}",
+                    entry.name(),
+                    entry.project_dir(),
+                    format!(
+                        "opened {} times, last {}",
+                        entry.opens(),
+                        format_age(entry.last_opened().elapsed().unwrap_or_default())
+                    )
+                    .dimmed()
+                );
+            }
+
+            Ok(())
+        }
+        Some("reset") => {
+            if !history_file.exists() || !confirm("Forget every opened project?", false) {
+                return Ok(());
+            }
+
+            fs::remove_file(history_file)?;
+            println!("Reset the history");
+
+            Ok(())
+        }
+        _ => {
+            eprintln!("Usage: workflows history <list|reset>");
+            Ok(())
+        }
+    }
+}
+
+/// Drops a project from the history once it's gone for good, warning the user if it couldn't be
+///
+/// # Parameters
+///
+/// - `project` The project that was removed
+pub(super) fn forget_history(project: &Repo) {
+    let forgotten = match history::history_file() {
+        Some(history_file) if history_file.exists() => {
+            update_state(&history_file, |history: &mut History| {
+                history.forget(project);
+            })
+        }
+        _ => Ok(()),
+    };
+
+    if let Err(err) = forgotten {
+        eprintln!(
+            "{} couldn't remove {} from the history: {}",
+            "WARNING".bright_yellow(),
+            project.name().bold(),
+            err
+        );
+    }
+}
@@ -0,0 +1,93 @@
+//! This module contains the logic for running the user's lifecycle hooks
+
+use std::io;
+
+use colored::Colorize;
+
+use crate::{
+    config::{hooks::Hook, WorkflowsConfig},
+    intergrations::{git, sh},
+    repo::Repo,
+    variables::env_name,
+};
+
+/// Runs the user's hook for a point in a project's lifecycle, if they've configured one.
+///
+/// The hook is run in the project's root, or its projects directory if the root doesn't exist
+/// yet (or anymore)
+///
+/// # Parameters
+///
+/// - `hook` The hook to run
+/// - `repo` The project the hook is being run for
+/// - `remote_url` The project's remote url. If `None`, the url of the project's `origin` remote
+///   is used when the project exists locally
+/// - `config` The user's config
+///
+/// # Returns
+///
+/// Whether the action should go ahead. Only a failing `pre_*` hook stops an action
+pub fn run_hook(
+    hook: Hook,
+    repo: &Repo,
+    remote_url: Option<&str>,
+    config: &WorkflowsConfig,
+) -> io::Result<bool> {
+    let command = match config.hooks().command(hook) {
+        Some(command) => command,
+        None => return Ok(true),
+    };
+
+    let home = dirs::home_dir().expect("Couldn't load home directory!");
+    let projects_dir = repo.project_dir().unwrap_or_default();
+    let project_root = repo.get_project_root().unwrap_or_default();
+
+    let remote_url = match remote_url {
+        Some(remote_url) => remote_url.to_string(),
+        None if project_root.is_dir() => {
+            git::remote_url(&project_root, "origin").unwrap_or_default()
+        }
+        None => String::new(),
+    };
+
+    let dir = match project_root.is_dir() {
+        true => project_root.clone(),
+        false => home.join(&projects_dir),
+    };
+    let dir = match dir.is_dir() {
+        true => dir,
+        false => home,
+    };
+
+    let envs = vec![
+        (env_name("hook"), hook.name().to_string()),
+        (env_name("project_name"), repo.name()),
+        (
+            env_name("project_root"),
+            project_root.to_string_lossy().to_string(),
+        ),
+        (env_name("projects_dir"), projects_dir),
+        (env_name("remote_url"), remote_url),
+    ];
+
+    let outcome = sh::run_streamed(&command, &dir, &envs)?;
+
+    if outcome.success() {
+        return Ok(true);
+    }
+
+    match hook.is_pre() {
+        true => eprintln!(
+            "{} {} hook failed, stopping",
+            "ERROR".bright_red(),
+            hook.name().bold()
+        ),
+        false => eprintln!(
+            "{} {} hook failed",
+            "WARNING".bright_yellow(),
+            hook.name().bold()
+        ),
+    }
+
+    Ok(!hook.is_pre())
+}
@@ -2,18 +2,43 @@ use std::io;
 
 use colored::*;
 
-use crate::{commands::open::get_local_project, config::WorkflowsConfig};
+use crate::{
+    commands::{
+        open::get_local_project,
+        tags::{existing_tags, format_project},
+    },
+    config::WorkflowsConfig,
+};
 
-/// Lists all local projects under their project directory
+/// Lists all local projects under their project directory, followed by the projects under each
+/// of their tags. Favourite projects are marked with a star
 ///
 /// # Parameters
 ///
-/// - `project_dir_filter` The passed in project diretory filter
+/// - `project_dir_filter` The passed in project diretory filter. A filter starting with `#` lists
+///   only the projects with that tag
 /// - `config` The user's config
 pub fn list_projects(
     project_dir_filter: Option<String>,
     config: WorkflowsConfig,
 ) -> io::Result<()> {
+    let tags = existing_tags(&config);
+
+    if let Some(tag) = project_dir_filter
+        .as_deref()
+        .and_then(|filter| filter.strip_prefix('#'))
+    {
+        let by_tag = tags.by_tag();
+        println!("{}", format!("#{}", tag).bold());
+        by_tag
+            .get(tag)
+            .into_iter()
+            .flatten()
+            .for_each(|project| println!("{}", format_project(project)));
+
+        return Ok(());
+    }
+
     config
         .general()
         .projects_dirs()
@@ -28,11 +53,24 @@ pub fn list_projects(
 
             get_local_project(project_dir.to_string())
                 .iter()
-                .map(|x| format!("• {}", x.name()))
+                .map(|x| match tags.is_favourite(x) {
+                    true => format!("• {} {}", x.name(), "★".bright_yellow()),
+                    false => format!("• {}", x.name()),
+                })
                 .for_each(|x| println!("{}", x));
 
             println!();
         });
 
+    if project_dir_filter.is_none() {
+        for (tag, projects) in tags.by_tag() {
+            println!("{}", format!("#{}", tag).bold());
+            projects
+                .iter()
+                .for_each(|project| println!("{}", format_project(project)));
+            println!();
+        }
+    }
+
     Ok(())
 }
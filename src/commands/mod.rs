@@ -1,5 +1,18 @@
 //! This module contains all the commands the program contains
 
+use std::{
+    io,
+    path::Path,
+    sync::atomic::{AtomicUsize, Ordering},
+    thread,
+    time::Duration,
+};
+
+use colored::Colorize;
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::state;
+
 // TODO: Add --generate-config flag for creating a default configuration
 // TODO: Add rename command
 
@@ -14,13 +27,18 @@ pub use delete::delete_project;
 
 mod open;
 pub use open::get_local_projects;
+pub use open::launch_project;
+pub use open::open_last_project;
+pub use open::open_previous_project;
 pub use open::open_project;
 pub use open::open_specific_project;
 
 mod new;
 pub use new::new_project;
+pub use new::NewProject;
 
 mod clone;
+pub use clone::clone_github_project;
 pub use clone::git_clone;
 
 mod borrow;
@@ -28,3 +46,180 @@ pub use borrow::borrow_project;
 
 mod list;
 pub use list::list_projects;
+
+mod template;
+pub use template::template_command;
+
+mod setup;
+pub use setup::setup_cloned_project;
+
+mod hooks;
+
+mod trash;
+pub use trash::trash_command;
+
+mod prune;
+pub use prune::prune_projects;
+
+mod archive;
+pub use archive::archive_command;
+pub use archive::unarchive_command;
+
+mod history;
+pub use history::history_command;
+
+mod tags;
+pub use tags::fav_command;
+pub use tags::tag_command;
+
+/// The most threads used to work on projects at once, such as when checking them before they're
+/// deleted. The work is mostly waiting on git and the network, so it isn't tied to the CPU count
+const MAX_THREADS: usize = 8;
+
+/// Uses state read from a file, falling back to the default state with a warning if the file
+/// couldn't be read
+///
+/// # Parameters
+///
+/// - `state` The result of reading the state
+pub fn state_or_default<T: Default>(state: io::Result<T>) -> T {
+    state.unwrap_or_else(|err| {
+        eprintln!("{} {}", "WARNING".bright_yellow(), err);
+        T::default()
+    })
+}
+
+/// Loads a state file, lets it be changed, then saves it, with the file locked throughout. If the
+/// file couldn't be parsed, the user is told where it was moved to
+///
+/// # Parameters
+///
+/// - `file`   The state file
+/// - `update` The change to make
+pub fn update_state<T: Serialize + DeserializeOwned + Default>(
+    file: &Path,
+    update: impl FnOnce(&mut T),
+) -> io::Result<()> {
+    if let Some(backup) = state::update_toml(file, update)? {
+        eprintln!(
+            "{} {} couldn't be parsed, so it was moved to {} and a new one was started",
+            "WARNING".bright_yellow(),
+            file.display(),
+            backup.display()
+        );
+    }
+
+    Ok(())
+}
+
+/// Asks the user a yes/no question
+///
+/// # Parameters
+///
+/// - `prompt`      The question to ask
+/// - `default_yes` Whether the default option should be yes
+///
+/// # Returns
+///
+/// `true` if the user answered yes
+pub fn confirm(prompt: impl Into<String>, default_yes: bool) -> bool {
+    casual::prompt(prompt)
+        .suffix(match default_yes {
+            true => " [Y/n] ",
+            false => " [y/N] ",
+        })
+        .default(match default_yes {
+            true => "y".to_string(),
+            false => "n".to_string(),
+        })
+        .matches(|s| matches!(&*s.trim().to_lowercase(), "n" | "no" | "y" | "yes"))
+        .map(|s| matches!(&*s.trim().to_lowercase(), "y" | "yes"))
+}
+
+/// Formats how long ago something happened, e.g. `3 days ago`
+///
+/// # Parameters
+///
+/// - `age` How long ago it happened
+pub fn format_age(age: Duration) -> String {
+    let seconds = age.as_secs();
+
+    let (count, unit) = match seconds {
+        0..=59 => return "just now".to_string(),
+        60..=3599 => (seconds / 60, "minute"),
+        3600..=86399 => (seconds / 3600, "hour"),
+        _ => (seconds / 86400, "day"),
+    };
+
+    match count {
+        1 => format!("1 {} ago", unit),
+        _ => format!("{} {}s ago", count, unit),
+    }
+}
+
+/// Runs a function on each item on a bounded number of threads
+///
+/// # Parameters
+///
+/// - `items` The items to run the function on
+/// - `f`     The function to run
+///
+/// # Returns
+///
+/// The results, in the same order as the items
+pub fn map_in_parallel<'a, T: Sync, R: Send>(
+    items: &'a [T],
+    f: impl Fn(&'a T) -> R + Sync,
+) -> Vec<R> {
+    let next = AtomicUsize::new(0);
+
+    let mut results: Vec<(usize, R)> = thread::scope(|scope| {
+        let workers: Vec<_> = (0..items.len().min(MAX_THREADS))
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut results = vec![];
+                    loop {
+                        let index = next.fetch_add(1, Ordering::Relaxed);
+                        match items.get(index) {
+                            Some(item) => results.push((index, f(item))),
+                            None => return results,
+                        }
+                    }
+                })
+            })
+            .collect();
+
+        workers
+            .into_iter()
+            .flat_map(|worker| worker.join().expect("A worker thread panicked"))
+            .collect()
+    });
+
+    results.sort_by_key(|(index, _)| *index);
+    results.into_iter().map(|(_, result)| result).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{format_age, map_in_parallel};
+
+    #[test]
+    fn format_age_works() {
+        assert_eq!(format_age(Duration::from_secs(5)), "just now");
+        assert_eq!(format_age(Duration::from_secs(60)), "1 minute ago");
+        assert_eq!(format_age(Duration::from_secs(7300)), "2 hours ago");
+        assert_eq!(format_age(Duration::from_secs(86400 * 90)), "90 days ago");
+    }
+
+    #[test]
+    fn map_in_parallel_keeps_order() {
+        let items: Vec<u64> = (0..50).collect();
+
+        let doubled = map_in_parallel(&items, |item| item * 2);
+
+        assert_eq!(doubled, (0..50).map(|item| item * 2).collect::<Vec<_>>());
+        assert!(map_in_parallel(&[] as &[u64], |item| *item).is_empty());
+    }
+}
@@ -1,90 +1,740 @@
 use std::{
-    fs,
-    io::{self, Write},
-    path::PathBuf,
-    process::Command,
+    fs, io,
+    os::unix::fs::symlink,
+    path::Path,
+    time::{Duration, Instant},
 };
 
 use colored::Colorize;
 
 use crate::{
-    config::{templates::WorkspaceTemplate, WorkflowsConfig},
-    intergrations::fzf::{get_project_dir, get_template},
+    config::{hooks::Hook, templates::WorkspaceTemplate, WorkflowsConfig},
+    intergrations::{
+        self,
+        fzf::{get_project_dir, get_template, select_option, SelectedTemplate},
+        sh::{self, format_elapsed},
+    },
+    repo::Repo,
+    variables::TemplateVariables,
 };
 
+use super::{
+    clone_github_project, confirm, get_local_projects, hooks::run_hook,
+    template::print_template_warnings,
+};
+
+/// The result of a single step of a template
+pub struct StepReport {
+    /// The command that was run
+    command: String,
+    /// Whether the step succeeded
+    success: bool,
+    /// The exit code of the command, `None` if it didn't exit normally
+    code: Option<i32>,
+    /// How long the step took
+    elapsed: Duration,
+    /// Why a skeleton failed to copy. Commands write their errors straight to the terminal, so
+    /// they aren't captured
+    error: Option<String>,
+}
+
+impl StepReport {
+    /// The command that was run
+    pub fn command(&self) -> &str {
+        self.command.as_ref()
+    }
+
+    /// Whether the step succeeded
+    pub fn success(&self) -> bool {
+        self.success
+    }
+
+    /// The exit code of the command, `None` if it didn't exit normally
+    pub fn code(&self) -> Option<i32> {
+        self.code
+    }
+
+    /// How long the step took
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    /// Why a skeleton failed to copy. Commands write their errors straight to the terminal, so
+    /// they aren't captured
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+}
+
+/// The outcome of running a template, containing a report for each step that was run
+pub enum TemplateResult {
+    /// Every step either succeeded or was allowed to fail
+    Completed(Vec<StepReport>),
+    /// A step failed, stopping the template. The failed step is the last report
+    Failed(Vec<StepReport>),
+}
+
+/// The template name that creates a project without a template, even when a default template is
+/// set
+pub const NO_TEMPLATE: &str = "none";
+
+/// The characters that can't be used in a project's name, as they break tmux session names or
+/// tmuxinator config files
+const INVALID_NAME_CHARS: [char; 3] = ['/', '.', ':'];
+
+/// The outcome of the new command
+pub enum NewProject {
+    /// A new project was created
+    Created(Repo),
+    /// A project with the same name already existed, and the user chose to open it instead
+    Existing(Repo),
+}
+
 /// Creates a new project in the selected project directory
 ///
 /// # Parameters
 ///
 /// - `project_name` The name of the project to create
+/// - `template_name` The name of the template to use, skipping the template prompt
+/// - `projects_dir` The projects directory to create the project in, skipping the directory prompt
 /// - `config` The users config
 ///
 /// # Returns
 ///
-/// A tuple in the format (project_name, projects_dir), otherwise `None` if the user didn't create
-/// a project
+/// The created project, or an existing project the user chose to open instead. Otherwise `None`
+/// if the user didn't create a project
 pub fn new_project(
     project_name: Option<String>,
+    template_name: Option<String>,
+    projects_dir: Option<String>,
     config: WorkflowsConfig,
-) -> io::Result<Option<(String, String)>> {
+) -> io::Result<Option<NewProject>> {
     if let Some(project_name) = project_name {
-        let projects_dir = match get_project_dir(&config) {
-            Some(projects_dir) => projects_dir,
-            None => return Ok(None),
+        if let Err(err) = validate_project_name(&project_name) {
+            eprintln!("{} {}", "ERROR".bright_red(), err);
+            return Ok(None);
+        }
+
+        let local_projects = get_local_projects(config.general().projects_dirs());
+        if let Some(existing) = local_projects.iter().find(|x| x.name() == project_name) {
+            return Ok(open_existing_instead(existing)
+                .then_some(NewProject::Existing(existing.to_owned())));
+        }
+
+        let projects_dir = match projects_dir {
+            Some(projects_dir) => match find_projects_dir(&projects_dir, &config) {
+                Some(projects_dir) => projects_dir,
+                None => {
+                    eprintln!(
+                        "{} {} is not one of your projects_dirs: {}",
+                        "ERROR".bright_red(),
+                        projects_dir.bold(),
+                        config.general().projects_dirs().join(", ")
+                    );
+                    return Ok(None);
+                }
+            },
+            None => match get_project_dir(&config) {
+                Some(projects_dir) => projects_dir,
+                None => return Ok(None),
+            },
         };
 
+        if config.github().enabled() {
+            let remote_projects = intergrations::gh::get_gh_repos(&local_projects);
+            if let Some(existing) = remote_projects.iter().find(|x| x.name() == project_name) {
+                if !clone_existing_instead(existing) {
+                    return Ok(None);
+                }
+
+                let mut existing = existing.to_owned();
+                if !clone_github_project(&mut existing, projects_dir, &config)? {
+                    return Ok(None);
+                }
+
+                return Ok(Some(NewProject::Existing(existing)));
+            }
+        }
+
         let project_dir = dirs::home_dir()
             .expect("Failed to get home directory")
             .join(projects_dir.clone())
             .join(&project_name);
 
+        let project = Repo::new(project_name.clone(), true, Some(projects_dir.clone()));
+
+        if !run_hook(Hook::PreCreate, &project, None, &config)? {
+            return Ok(None);
+        }
+
+        print_template_warnings(&config);
+
+        let template_name = template_name.or(config.general().default_template_for(&projects_dir));
+
+        let template = match select_template(template_name, &config) {
+            Ok(Some(SelectedTemplate::Github(template_repo))) => {
+                let created = create_from_github_template(
+                    &project_name,
+                    &template_repo,
+                    &projects_dir,
+                    &config,
+                )?;
+
+                if !created {
+                    return Ok(None);
+                }
+
+                run_hook(Hook::PostCreate, &project, None, &config)?;
+
+                return Ok(Some(NewProject::Created(project)));
+            }
+            Ok(Some(SelectedTemplate::Workflows(template))) => {
+                template.resolve(&config.templates()).map(Some)
+            }
+            Ok(None) => Ok(None),
+            Err(err) => {
+                eprintln!("{} {}", "ERROR".bright_red(), err);
+                return Ok(None);
+            }
+        };
+
+        let template = match template {
+            Ok(template) => template,
+            Err(err) => {
+                eprintln!("{} {}", "ERROR".bright_red(), err);
+                return Ok(None);
+            }
+        };
+
+        let mut variables = TemplateVariables::new(&project_name, &projects_dir, &project_dir);
+        if let Some(template) = &template {
+            ask_prompts(template, &mut variables, &config);
+        }
+
         fs::create_dir_all(&project_dir)?;
 
-        let template = get_template(config);
-        run_template(template, &project_name, project_dir)?;
+        let result = match &template {
+            Some(template) => run_template(template, &variables, &project_dir)?,
+            None => TemplateResult::Completed(vec![]),
+        };
+
+        if let TemplateResult::Failed(reports) = result {
+            let failed_step = reports.last().expect("a failed template has a failed step");
+
+            eprintln!(
+                "{} template step failed: {}",
+                "ERROR".bright_red(),
+                failed_step.command().bold()
+            );
+            if let Some(error) = failed_step.error() {
+                eprintln!("\n{}", error);
+            }
+            println!();
+
+            if confirm(
+                format!(
+                    "Remove the partially created project at {}?",
+                    project_dir.display()
+                ),
+                true,
+            ) {
+                fs::remove_dir_all(&project_dir)?;
+            }
 
-        return Ok(Some((project_name, projects_dir)));
+            return Ok(None);
+        }
+
+        let publish = template
+            .as_ref()
+            .and_then(|template| template.publish())
+            .unwrap_or(config.github().publish());
+
+        if publish {
+            publish_project(
+                &project_name,
+                &project_dir,
+                template.as_ref(),
+                &variables,
+                &config,
+            )?;
+        }
+
+        run_hook(Hook::PostCreate, &project, None, &config)?;
+
+        return Ok(Some(NewProject::Created(project)));
     }
 
     Ok(None)
 }
 
-/// Runs the commands associated with a template
+/// Checks that a project name can be used for a directory, tmux session and tmuxinator config
 ///
 /// # Parameters
 ///
-/// - `template` The templae to execute
-/// - `project_name` The name of the project being created
-/// - `project_dir` The directory the project is in, e.g. `~/Projects/workflows`
+/// - `project_name` The name to check
 ///
 /// # Returns
 ///
-/// An IO result
-fn run_template(
-    template: Option<WorkspaceTemplate>,
+/// An error describing why the name is invalid
+fn validate_project_name(project_name: &str) -> Result<(), String> {
+    if project_name.trim().is_empty() {
+        return Err("project names can't be empty".to_string());
+    }
+
+    if project_name.starts_with('-') {
+        return Err(format!(
+            "project names can't start with '-': {}",
+            project_name
+        ));
+    }
+
+    if let Some(c) = project_name
+        .chars()
+        .find(|c| c.is_whitespace() || INVALID_NAME_CHARS.contains(c))
+    {
+        return Err(format!(
+            "project names can't contain {:?}: {}",
+            c, project_name
+        ));
+    }
+
+    Ok(())
+}
+
+/// Tells the user a local project with the same name already exists, asking if they'd like to
+/// open it instead
+///
+/// # Parameters
+///
+/// - `existing` The existing project
+fn open_existing_instead(existing: &Repo) -> bool {
+    eprintln!(
+        "{} a project named {} already exists at {}",
+        "ERROR".bright_red(),
+        existing.name().bold(),
+        existing
+            .get_project_root()
+            .map(|root| root.display().to_string())
+            .unwrap_or_default()
+    );
+
+    confirm("Open the existing project instead?", true)
+}
+
+/// Tells the user a GitHub repo with the same name already exists, asking if they'd like to
+/// clone it instead
+///
+/// # Parameters
+///
+/// - `existing` The existing GitHub repo
+fn clone_existing_instead(existing: &Repo) -> bool {
+    eprintln!(
+        "{} a repo named {} already exists on GitHub",
+        "ERROR".bright_red(),
+        existing.name().bold()
+    );
+
+    confirm("Clone and open the existing repo instead?", true)
+}
+
+/// Finds the configured projects directory matching the given one, ignoring trailing slashes
+///
+/// # Parameters
+///
+/// - `projects_dir` The projects directory to look for, e.g. `Work`
+/// - `config` The users config
+fn find_projects_dir(projects_dir: &str, config: &WorkflowsConfig) -> Option<String> {
+    config
+        .general()
+        .projects_dirs()
+        .into_iter()
+        .find(|x| x.trim_end_matches('/') == projects_dir.trim_end_matches('/'))
+}
+
+/// Gets the template to create the project with. If no template name is given, the user is
+/// prompted to select one. Names containing a `/` are treated as GitHub template repos, e.g.
+/// `owner/template`
+///
+/// # Parameters
+///
+/// - `template_name` The name of the template to use
+/// - `config` The users config
+///
+/// # Returns
+///
+/// The selected template, `None` if the user selected no template, or an error if no template
+/// has the given name
+fn select_template(
+    template_name: Option<String>,
+    config: &WorkflowsConfig,
+) -> Result<Option<SelectedTemplate>, String> {
+    let template_name = match template_name {
+        Some(template_name) => template_name,
+        None => return Ok(get_template(config.clone())),
+    };
+
+    if template_name == NO_TEMPLATE {
+        return Ok(None);
+    }
+
+    if template_name.contains('/') {
+        return Ok(Some(SelectedTemplate::Github(template_name)));
+    }
+
+    match config
+        .templates()
+        .into_iter()
+        .find(|template| template.name() == template_name)
+    {
+        Some(template) => Ok(Some(SelectedTemplate::Workflows(template))),
+        None => Err(format!("no template named {} exists", template_name)),
+    }
+}
+
+/// Creates a project from a GitHub template repo using `gh`, cloning it into the projects
+/// directory
+///
+/// # Parameters
+///
+/// - `project_name` The name of the project to create
+/// - `template_repo` The template repo to create the project from, e.g. `owner/template`
+/// - `projects_dir` The projects directory to clone the project into
+/// - `config` The users config
+///
+/// # Returns
+///
+/// Whether the project was created
+fn create_from_github_template(
+    project_name: &str,
+    template_repo: &str,
+    projects_dir: &str,
+    config: &WorkflowsConfig,
+) -> io::Result<bool> {
+    if !config.github().enabled() {
+        eprintln!(
+            "{} creating from a GitHub template can only be done when github intergration is enabled",
+            "ERROR".bright_red()
+        );
+        return Ok(false);
+    }
+
+    println!(
+        "{}",
+        format!("Creating {} from {}", project_name, template_repo).bold()
+    );
+
+    let created = intergrations::gh::create_from_template(
+        project_name,
+        template_repo,
+        projects_dir,
+        &config.github().repo_visibility(),
+    )?;
+
+    if !created {
+        eprintln!(
+            "{} failed to create {} from {}",
+            "ERROR".bright_red(),
+            project_name.bold(),
+            template_repo
+        );
+    }
+
+    Ok(created)
+}
+
+/// Publishes a new project to GitHub, initialising a git repo and making an initial commit first
+/// if needed. Failures are reported, but don't stop the project from being opened
+///
+/// # Parameters
+///
+/// - `project_name` The name of the project
+/// - `project_dir` The directory the project is in
+/// - `template` The template the project was created with
+/// - `variables` The variables to substitute into the repo description
+/// - `config` The users config
+fn publish_project(
     project_name: &str,
-    project_dir: PathBuf,
+    project_dir: &Path,
+    template: Option<&WorkspaceTemplate>,
+    variables: &TemplateVariables,
+    config: &WorkflowsConfig,
 ) -> io::Result<()> {
-    if template.is_none() {
+    if !config.github().enabled() {
+        eprintln!(
+            "{} publishing can only be done when github intergration is enabled",
+            "ERROR".bright_red()
+        );
+        return Ok(());
+    }
+
+    if intergrations::git::has_remote(project_dir, "origin") {
+        println!("Project already has a remote, skipping publishing");
+        return Ok(());
+    }
+
+    println!(
+        "{}",
+        format!("Publishing {} to GitHub", project_name).bold()
+    );
+
+    if !intergrations::git::is_repo(project_dir) && !intergrations::git::init(project_dir)? {
+        eprintln!("{} failed to initialise a git repo", "ERROR".bright_red());
         return Ok(());
     }
-    let template = template.expect("checked");
 
-    for command in template.commands() {
+    if !intergrations::git::commit_all(project_dir, "Initial commit")? {
+        eprintln!("{} failed to make the initial commit", "ERROR".bright_red());
+        return Ok(());
+    }
+
+    let visibility = template
+        .and_then(|template| template.visibility())
+        .unwrap_or(config.github().repo_visibility());
+
+    let description = match template.and_then(|template| template.description()) {
+        Some(description) => variables.substitute(&description),
+        None => casual::prompt("Description: ").default(String::new()).get(),
+    };
+
+    match intergrations::gh::publish_repo(project_dir, project_name, &visibility, &description)? {
+        true => println!(
+            "[{}] published as a {} repo\n",
+            "✓".bright_green().bold(),
+            visibility
+        ),
+        false => eprintln!(
+            "{} failed to publish {} to GitHub\n",
+            "ERROR".bright_red(),
+            project_name.bold()
+        ),
+    }
+
+    Ok(())
+}
+
+/// Asks the user the template's prompts, storing their answers as variables
+///
+/// # Parameters
+///
+/// - `template` The template being used
+/// - `variables` The variables to add the answers to
+/// - `config` The users config
+fn ask_prompts(
+    template: &WorkspaceTemplate,
+    variables: &mut TemplateVariables,
+    config: &WorkflowsConfig,
+) {
+    for prompt in template.prompts() {
+        let answer = match prompt.options().is_empty() {
+            true => casual::prompt(prompt.message())
+                .default(prompt.default_answer())
+                .get(),
+            false => select_option(prompt.message(), prompt.options(), config)
+                .unwrap_or(prompt.default_answer()),
+        };
+
+        variables.insert(prompt.name(), answer);
+    }
+}
+
+/// Runs the commands associated with a template after copying its skeletons, stopping at the
+/// first failing step unless it is marked with `continue_on_error`
+///
+/// # Parameters
+///
+/// - `template` The templae to execute
+/// - `variables` The variables to expose to the template's commands
+/// - `project_dir` The directory the project is in, e.g. `~/Projects/workflows`
+///
+/// # Returns
+///
+/// An IO result containing whether the template completed, and a report of each step
+pub fn run_template(
+    template: &WorkspaceTemplate,
+    variables: &TemplateVariables,
+    project_dir: &Path,
+) -> io::Result<TemplateResult> {
+    let mut reports = vec![];
+
+    for skeleton in template.skeletons() {
+        let command = format!("copy {}", skeleton.display());
         println!("{}", command.bold());
 
-        let output = Command::new("sh")
-            .arg("-c")
-            .current_dir(&project_dir)
-            .env("WORKFLOWS_PROJECT_NAME", project_name)
-            .arg(command)
-            .output()?;
+        let start = Instant::now();
+        let copied = copy_skeleton(&skeleton, project_dir, variables);
+
+        reports.push(StepReport {
+            command,
+            success: copied.is_ok(),
+            code: None,
+            elapsed: start.elapsed(),
+            error: copied.err().map(|err| err.to_string()),
+        });
+
+        let report = reports.last().expect("just pushed");
+        print_step_result(report.success(), report.elapsed());
+
+        if !report.success() {
+            return Ok(TemplateResult::Failed(reports));
+        }
+    }
+
+    let envs = variables.envs();
+
+    for step in template.commands() {
+        println!("{}", step.command().bold());
+
+        let outcome = sh::run_streamed(step.command(), project_dir, &envs)?;
+
+        reports.push(StepReport {
+            command: step.command().to_string(),
+            success: outcome.success(),
+            code: outcome.code(),
+            elapsed: outcome.elapsed(),
+            error: None,
+        });
+
+        let report = reports.last().expect("just pushed");
+        print_step_result(report.success(), report.elapsed());
+
+        if outcome.success() {
+            continue;
+        }
+
+        if step.continue_on_error() {
+            println!(
+                "{} step failed, continuing as it allows errors\n",
+                "WARNING".bright_yellow()
+            );
+            continue;
+        }
+
+        return Ok(TemplateResult::Failed(reports));
+    }
+
+    Ok(TemplateResult::Completed(reports))
+}
+
+/// Prints whether a step succeeded and how long it took
+///
+/// # Parameters
+///
+/// - `success` Whether the step succeeded
+/// - `elapsed` How long the step took
+pub(super) fn print_step_result(success: bool, elapsed: Duration) {
+    match success {
+        true => println!(
+            "[{}] finished in {}\n",
+            "✓".bright_green().bold(),
+            format_elapsed(elapsed)
+        ),
+        false => println!(
+            "[{}] failed after {}\n",
+            "⨯".bright_red().bold(),
+            format_elapsed(elapsed)
+        ),
+    }
+}
+
+/// Recursively copies the contents of a skeleton directory into a project, substituting
+/// `{{variable}}` placeholders in file names and the contents of text files. Symlinks are
+/// copied as links, rather than followed
+///
+/// # Parameters
+///
+/// - `skeleton` The directory to copy from
+/// - `destination` The directory to copy into
+/// - `variables` The variables to substitute
+fn copy_skeleton(
+    skeleton: &Path,
+    destination: &Path,
+    variables: &TemplateVariables,
+) -> io::Result<()> {
+    for entry in fs::read_dir(skeleton)? {
+        let entry = entry?;
+
+        let file_name = variables.substitute(&entry.file_name().to_string_lossy());
+        let target = destination.join(file_name);
 
-        io::stdout().write_all(&output.stdout)?;
-        io::stderr().write_all(&output.stderr)?;
+        let file_type = entry.file_type()?;
 
-        println!();
+        if file_type.is_symlink() {
+            symlink(fs::read_link(entry.path())?, &target)?;
+            continue;
+        }
+
+        if file_type.is_dir() {
+            fs::create_dir_all(&target)?;
+            copy_skeleton(&entry.path(), &target, variables)?;
+            continue;
+        }
+
+        let contents = fs::read(entry.path())?;
+        match String::from_utf8(contents) {
+            Ok(text) => fs::write(&target, variables.substitute(&text))?,
+            // Binary files are copied as is
+            Err(err) => fs::write(&target, err.into_bytes())?,
+        }
+        fs::set_permissions(&target, entry.metadata()?.permissions())?;
     }
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, os::unix::fs::symlink, path::Path};
+
+    use super::{copy_skeleton, validate_project_name};
+    use crate::{test_utils::TempDir, variables::TemplateVariables};
+
+    #[test]
+    fn copy_skeleton_substitutes_names_and_contents() {
+        let root = TempDir::new("skeleton");
+        let skeleton = root.join("skeleton");
+        let project = root.join("project");
+
+        fs::create_dir_all(skeleton.join(".github/workflows")).unwrap();
+        fs::create_dir_all(&project).unwrap();
+        fs::write(skeleton.join("README.md"), "# {{project_name}}\n").unwrap();
+        fs::write(skeleton.join(".github/workflows/ci.yml"), "name: ci").unwrap();
+        fs::write(skeleton.join("{{project_name}}.txt"), [0xff, 0xfe]).unwrap();
+        // A link back up the tree would be copied forever if it was followed
+        symlink("..", skeleton.join(".github/parent")).unwrap();
+
+        let mut variables = TemplateVariables::default();
+        variables.insert("project_name", "demo");
+
+        copy_skeleton(&skeleton, &project, &variables).unwrap();
+
+        assert_eq!(
+            fs::read_to_string(project.join("README.md")).unwrap(),
+            "# demo\n"
+        );
+        assert_eq!(
+            fs::read_to_string(project.join(".github/workflows/ci.yml")).unwrap(),
+            "name: ci"
+        );
+        assert_eq!(
+            fs::read(project.join("demo.txt")).unwrap(),
+            vec![0xff, 0xfe]
+        );
+        assert_eq!(
+            fs::read_link(project.join(".github/parent")).unwrap(),
+            Path::new("..")
+        );
+    }
+
+    #[test]
+    fn validate_project_name_works() {
+        assert!(validate_project_name("workflows").is_ok());
+        assert!(validate_project_name("my-project_2").is_ok());
+
+        assert!(validate_project_name("").is_err());
+        assert!(validate_project_name("--template").is_err());
+        assert!(validate_project_name("my project").is_err());
+        assert!(validate_project_name("clients/project").is_err());
+        assert!(validate_project_name("project.rs").is_err());
+        assert!(validate_project_name("project:1").is_err());
+    }
+}
@@ -1,46 +1,182 @@
-use std::{fs, io};
+use std::{fs, io, time::SystemTime};
 
-use crate::config::WorkflowsConfig;
+use colored::Colorize;
+
+use crate::config::{hooks::Hook, WorkflowsConfig};
 use crate::intergrations;
-use crate::intergrations::fzf::get_project_dir;
+use crate::intergrations::fzf::{get_project_dir, ProjectFilter};
 use crate::repo::Repo;
+use crate::state::history::{self, History};
+
+use super::{
+    archive::unarchive, clone_github_project, confirm, hooks::run_hook, state_or_default,
+    update_state,
+};
 
 /// Runs fzf with the user's projects, opening the one they select in a tmuxinator session
 ///
 /// # Parameters
 ///
+/// - `filter` Which local projects to show
 /// - `config` The user's config
-pub fn open_project(config: WorkflowsConfig) -> io::Result<()> {
+pub fn open_project(filter: ProjectFilter, config: WorkflowsConfig) -> io::Result<()> {
     let selected_project =
-        intergrations::fzf::run_fzf(&config.fzf().open_prompt(), true, true, &config);
+        intergrations::fzf::run_fzf(&config.fzf().open_prompt(), true, true, &filter, &config);
 
     if let Some(mut selected_project) = selected_project {
+        if selected_project.archived() {
+            selected_project = match unarchive(Some(selected_project.name()), &config)? {
+                Some(project) => project,
+                None => return Ok(()),
+            };
+        }
+
         if !selected_project.local() {
             if config.github().confirm_cloning()
-                && !casual::prompt("Project is not local, clone it?")
-                    .suffix(" [Y/n] ")
-                    .default("y".to_string())
-                    .matches(|s| matches!(&*s.trim().to_lowercase(), "n" | "no" | "y" | "yes"))
-                    .map(|s| matches!(&*s.to_lowercase(), "y" | "yes"))
+                && !confirm("Project is not local, clone it?", true)
             {
                 return Ok(());
             }
 
-            match get_project_dir(&config) {
-                Some(project_dir) => {
-                    intergrations::gh::clone_repo(&selected_project, project_dir.clone())?;
-                    selected_project.set_project_dir(Some(project_dir));
-                }
+            let project_dir = match get_project_dir(&config) {
+                Some(project_dir) => project_dir,
                 None => return Ok(()),
+            };
+
+            if !clone_github_project(&mut selected_project, project_dir, &config)? {
+                return Ok(());
             }
         }
 
-        intergrations::tmuxinator::run_tmuxinator(&selected_project, config.tmuxinator())?;
+        launch_project(&selected_project, &config)?;
+    }
+
+    Ok(())
+}
+
+/// Opens a project in a tmuxinator session, running the user's open hooks around it
+///
+/// # Parameters
+///
+/// - `project` The project to open
+/// - `config` The user's config
+pub fn launch_project(project: &Repo, config: &WorkflowsConfig) -> io::Result<()> {
+    if !run_hook(Hook::PreOpen, project, None, config)? {
+        return Ok(());
+    }
+
+    // Recorded before the session starts, as attaching blocks until the user detaches
+    if config.history().enabled() {
+        if let Err(err) = record_open(project) {
+            eprintln!(
+                "{} couldn't record opening {}, as {}",
+                "WARNING".bright_yellow(),
+                project.name().bold(),
+                err
+            );
+        }
     }
 
+    intergrations::tmuxinator::run_tmuxinator(project, config.tmuxinator())?;
+
+    run_hook(Hook::PostOpen, project, None, config)?;
+
     Ok(())
 }
 
+/// Records that a project was opened in the history of opened projects
+///
+/// # Parameters
+///
+/// - `project` The project that was opened
+fn record_open(project: &Repo) -> io::Result<()> {
+    let history_file = history::history_file()
+        .ok_or_else(|| io::Error::other("there's no directory for the history"))?;
+
+    update_state(&history_file, |history: &mut History| {
+        history.record(project, SystemTime::now())
+    })
+}
+
+/// Reopens the most recently opened project
+///
+/// # Parameters
+///
+/// - `config` The user's config
+pub fn open_last_project(config: WorkflowsConfig) -> io::Result<()> {
+    match recent_projects(&config).first() {
+        Some(project) => launch_project(project, &config),
+        None => {
+            println!("No project has been opened yet!");
+            Ok(())
+        }
+    }
+}
+
+/// Opens the project that was open before the current one, so running it again switches back,
+/// like `cd -`. The current project is the tmux session workflows is running in, or the most
+/// recently opened project outside of tmux
+///
+/// # Parameters
+///
+/// - `config` The user's config
+pub fn open_previous_project(config: WorkflowsConfig) -> io::Result<()> {
+    let recent_projects = recent_projects(&config);
+    let current_project = intergrations::tmux::current_session()
+        .or_else(|| recent_projects.first().map(|project| project.name()));
+
+    match previous_project(&recent_projects, current_project.as_deref()) {
+        Some(project) => launch_project(project, &config),
+        None => {
+            println!("No previous project has been opened!");
+            Ok(())
+        }
+    }
+}
+
+/// Gets the projects in the history of opened projects that still exist, most recently opened
+/// first
+///
+/// # Parameters
+///
+/// - `config` The user's config
+fn recent_projects(config: &WorkflowsConfig) -> Vec<Repo> {
+    if !config.history().enabled() {
+        eprintln!(
+            "{} the history is disabled, so no opened projects are known",
+            "WARNING".bright_yellow()
+        );
+        return vec![];
+    }
+
+    let history = match history::history_file() {
+        Some(history_file) => state_or_default(History::load(&history_file)),
+        None => return vec![],
+    };
+
+    history
+        .entries()
+        .into_iter()
+        .map(|entry| Repo::new(entry.name(), true, Some(entry.project_dir())))
+        .filter(|project| project.get_project_root().is_some_and(|root| root.is_dir()))
+        .collect()
+}
+
+/// Picks the most recently opened project that isn't the current one
+///
+/// # Parameters
+///
+/// - `recent_projects` The opened projects, most recently opened first
+/// - `current_project` The name of the current project
+fn previous_project<'a>(
+    recent_projects: &'a [Repo],
+    current_project: Option<&str>,
+) -> Option<&'a Repo> {
+    recent_projects
+        .iter()
+        .find(|project| Some(project.name().as_str()) != current_project)
+}
+
 /// Opens a local project with the given name
 ///
 /// # Parameters
@@ -55,7 +191,7 @@ pub fn open_specific_project(project_name: String, config: WorkflowsConfig) -> i
     let matching_project = local_projects.iter().find(|x| x.name() == project_name);
 
     match matching_project {
-        Some(repo) => intergrations::tmuxinator::run_tmuxinator(repo, config.tmuxinator())?,
+        Some(repo) => launch_project(repo, &config)?,
         None => println!("Project not found in local projects folder!"),
     }
 
@@ -116,3 +252,30 @@ pub fn get_local_project(project_dir: String) -> Vec<Repo> {
 
     local_repos
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::repo::Repo;
+
+    use super::previous_project;
+
+    #[test]
+    fn previous_project_works() {
+        let recent_projects = vec![
+            Repo::new("workflows", true, Some("Projects/")),
+            Repo::new("dotfiles", true, Some("Projects/")),
+            Repo::new("website", true, Some("Projects/")),
+        ];
+
+        let previous = |current| previous_project(&recent_projects, current).map(|x| x.name());
+
+        assert_eq!(previous(Some("workflows")), Some("dotfiles".to_string()));
+        // Outside of the most recent project, it's the one to go back to
+        assert_eq!(previous(Some("website")), Some("workflows".to_string()));
+        assert_eq!(previous(None), Some("workflows".to_string()));
+        assert_eq!(
+            previous_project(&recent_projects[..1], Some("workflows")),
+            None
+        );
+    }
+}
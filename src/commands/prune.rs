@@ -0,0 +1,328 @@
+//! This module contains the logic for the prune command, which suggests projects that haven't
+//! been touched in a while for removal
+
+use std::{
+    fs, io,
+    io::{stdout, Write},
+    path::Path,
+    time::{Duration, SystemTime},
+};
+
+use colored::Colorize;
+
+use crate::{config::WorkflowsConfig, intergrations, repo::Repo};
+
+use super::{
+    delete::{remove_checked_project, summarise, ProjectCheck, Removal},
+    format_age, get_local_projects, map_in_parallel,
+};
+
+/// How long a project has to go untouched before it's suggested, when the user doesn't say
+const DEFAULT_OLDER_THAN: &str = "90d";
+
+/// Directories that aren't walked when finding a project's last modification, as they hold
+/// dependencies or build output rather than the user's own edits
+const SKIPPED_DIRS: [&str; 3] = [".git", "node_modules", "target"];
+
+/// The number of seconds in a day
+const SECONDS_IN_DAY: u64 = 60 * 60 * 24;
+
+/// When a project was last worked on
+struct Activity<'a> {
+    /// The project
+    repo: &'a Repo,
+    /// When the last commit on any branch was made
+    last_commit: Option<SystemTime>,
+    /// When a file in the project was last modified
+    last_modified: Option<SystemTime>,
+}
+
+impl<'a> Activity<'a> {
+    /// Finds when a project was last worked on
+    ///
+    /// # Parameters
+    ///
+    /// - `repo` The project to scan
+    fn scan(repo: &'a Repo) -> Self {
+        let project_root = repo.get_project_root().expect("Failed to get project root");
+
+        Activity {
+            repo,
+            last_commit: intergrations::git::last_commit(&project_root),
+            last_modified: last_modified(&project_root),
+        }
+    }
+
+    /// When the project was last worked on, either committed to or modified
+    fn last_active(&self) -> Option<SystemTime> {
+        self.last_commit.max(self.last_modified)
+    }
+
+    /// Whether the project has gone untouched for at least the given time
+    ///
+    /// # Parameters
+    ///
+    /// - `older_than` How long the project has to have gone untouched
+    fn stale(&self, older_than: Duration) -> bool {
+        self.last_active()
+            .is_none_or(|last_active| last_active.elapsed().unwrap_or_default() >= older_than)
+    }
+}
+
+/// A stale project that might be pruned
+struct PruneCandidate<'a> {
+    /// The project's delete checks
+    check: ProjectCheck<'a>,
+    /// When the project was last worked on
+    activity: &'a Activity<'a>,
+}
+
+/// Suggests projects that haven't been committed to or modified in a while, letting the user
+/// pick which ones to remove. Projects that are fully pushed and clean are listed first, followed
+/// by the longest untouched
+///
+/// # Parameters
+///
+/// - `older_than` How long projects have to have gone untouched, e.g. `90d`
+/// - `config`     The user's config
+pub fn prune_projects(older_than: Option<String>, config: WorkflowsConfig) -> io::Result<()> {
+    let older_than = older_than.unwrap_or(DEFAULT_OLDER_THAN.to_string());
+    let age = match parse_age(&older_than) {
+        Some(age) => age,
+        None => {
+            eprintln!(
+                "{} {} isn't a valid age, try something like {}",
+                "ERROR".bright_red(),
+                older_than.bold(),
+                DEFAULT_OLDER_THAN.bold()
+            );
+            return Ok(());
+        }
+    };
+
+    let projects = get_local_projects(config.general().projects_dirs());
+
+    print!(
+        "[{}] scanning {} projects...",
+        "~".bright_yellow(),
+        projects.len()
+    );
+    stdout().flush()?;
+
+    // Finding when a project was last worked on is cheap, so the safety checks, which may fetch
+    // remotes, are only run on the stale projects
+    let stale: Vec<Activity> = map_in_parallel(&projects, Activity::scan)
+        .into_iter()
+        .filter(|activity| activity.stale(age))
+        .collect();
+
+    let mut candidates: Vec<PruneCandidate> = map_in_parallel(&stale, |activity| PruneCandidate {
+        check: ProjectCheck::run(activity.repo, &config),
+        activity,
+    });
+    println!(
+        "\r[{}] scanned {} projects   \n",
+        "✓".bright_green().bold(),
+        projects.len()
+    );
+
+    if candidates.is_empty() {
+        println!("No projects have gone untouched for {}", older_than);
+        return Ok(());
+    }
+
+    candidates.sort_by_key(|candidate| {
+        (
+            !candidate.check.safe(&config),
+            candidate.activity.last_active(),
+        )
+    });
+
+    let width = candidates
+        .iter()
+        .map(|candidate| candidate.check.repo().name().len())
+        .max()
+        .unwrap_or_default();
+
+    let lines: Vec<String> = candidates
+        .iter()
+        .map(|candidate| {
+            let (status, _) = summarise(&candidate.check, &config);
+            format!(
+                "{:width$}  {}  last commit {}, last modified {}",
+                candidate.check.repo().name(),
+                status.clear(),
+                format_last(candidate.activity.last_commit),
+                format_last(candidate.activity.last_modified),
+            )
+        })
+        .collect();
+
+    let selected = intergrations::fzf::select_options(
+        format!("Select projects untouched for {} to prune: ", older_than),
+        &lines,
+        &config,
+    );
+
+    let selected: Vec<&PruneCandidate> = candidates
+        .iter()
+        .zip(&lines)
+        .filter(|(_, line)| selected.iter().any(|selected| selected == line.trim()))
+        .map(|(candidate, _)| candidate)
+        .collect();
+
+    if selected.is_empty() {
+        return Ok(());
+    }
+
+    for candidate in &selected {
+        let (status, details) = summarise(&candidate.check, &config);
+        println!(
+            "{:width$}  {}  {}",
+            candidate.check.repo().name(),
+            status,
+            details.join(", ").dimmed()
+        );
+    }
+    println!();
+
+    let removal = casual::prompt(format!("Remove the {} selected projects?", selected.len()))
+        .suffix(" [t]rash, [d]elete, [a]rchive or [N]othing: ")
+        .default("n".to_string())
+        .matches(|s| {
+            matches!(
+                &*s.trim().to_lowercase(),
+                "t" | "trash" | "d" | "delete" | "a" | "archive" | "n" | "nothing"
+            )
+        })
+        .get();
+
+    let removal = match &*removal.trim().to_lowercase() {
+        "t" | "trash" => Removal::Trash,
+        "d" | "delete" => Removal::Delete,
+        "a" | "archive" => Removal::Archive,
+        _ => return Ok(()),
+    };
+
+    for candidate in selected {
+        println!();
+        remove_checked_project(&candidate.check, removal, &config)?;
+    }
+
+    Ok(())
+}
+
+/// Formats when something last happened, for the list of projects
+///
+/// # Parameters
+///
+/// - `time` When it last happened, `None` if it never has
+fn format_last(time: Option<SystemTime>) -> String {
+    match time {
+        Some(time) => format_age(time.elapsed().unwrap_or_default()),
+        None => "never".to_string(),
+    }
+}
+
+/// Parses an age such as `90d`, `12w` or `1y`
+///
+/// # Parameters
+///
+/// - `age` The age to parse, a number followed by `d` for days, `w` for weeks or `y` for years
+///
+/// # Returns
+///
+/// `None` if the age isn't valid
+fn parse_age(age: &str) -> Option<Duration> {
+    let unit = age.chars().last()?;
+    let count: u64 = age[..age.len() - unit.len_utf8()].parse().ok()?;
+
+    let days = match unit {
+        'd' => Some(count),
+        'w' => count.checked_mul(7),
+        'y' => count.checked_mul(365),
+        _ => return None,
+    };
+
+    Some(Duration::from_secs(days?.checked_mul(SECONDS_IN_DAY)?))
+}
+
+/// Finds when anything in a directory was last modified, skipping dependencies and build output
+///
+/// # Parameters
+///
+/// - `dir` The directory to search
+///
+/// # Returns
+///
+/// `None` if the directory is empty or couldn't be read
+fn last_modified(dir: &Path) -> Option<SystemTime> {
+    fs::read_dir(dir)
+        .ok()?
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let metadata = entry.path().symlink_metadata().ok()?;
+
+            if !metadata.is_dir() {
+                return metadata.modified().ok();
+            }
+
+            let name = entry.file_name();
+            if SKIPPED_DIRS.iter().any(|skipped| name == *skipped) {
+                return None;
+            }
+
+            metadata.modified().ok().max(last_modified(&entry.path()))
+        })
+        .max()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        fs,
+        time::{Duration, SystemTime},
+    };
+
+    use crate::test_utils::TempDir;
+
+    use super::{last_modified, parse_age};
+
+    #[test]
+    fn parse_age_works() {
+        let day = 60 * 60 * 24;
+
+        assert_eq!(parse_age("90d"), Some(Duration::from_secs(90 * day)));
+        assert_eq!(parse_age("2w"), Some(Duration::from_secs(14 * day)));
+        assert_eq!(parse_age("1y"), Some(Duration::from_secs(365 * day)));
+        assert_eq!(parse_age("90"), None);
+        assert_eq!(parse_age("d"), None);
+        assert_eq!(parse_age("-1d"), None);
+        assert_eq!(parse_age(&format!("{}y", u64::MAX / 2)), None);
+        assert_eq!(parse_age(&format!("{}d", u64::MAX)), None);
+        assert_eq!(parse_age(""), None);
+    }
+
+    #[test]
+    fn last_modified_works() {
+        let dir = TempDir::new("prune");
+        let old = SystemTime::now() - Duration::from_secs(60 * 60 * 24 * 100);
+
+        fs::create_dir_all(dir.join("src")).unwrap();
+        fs::create_dir_all(dir.join("target")).unwrap();
+        fs::write(dir.join("src/main.rs"), "fn main() {}").unwrap();
+        fs::write(dir.join("target/build"), "").unwrap();
+
+        let main = fs::File::options()
+            .write(true)
+            .open(dir.join("src/main.rs"))
+            .unwrap();
+        main.set_modified(old).unwrap();
+        for path in [dir.join("src"), dir.join("target"), dir.to_path_buf()] {
+            fs::File::open(path).unwrap().set_modified(old).unwrap();
+        }
+
+        // The build output is newer, but it's skipped
+        assert_eq!(last_modified(&dir), Some(old));
+    }
+}
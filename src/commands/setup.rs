@@ -0,0 +1,62 @@
+//! This module contains the logic for setting up projects after they're cloned
+
+use std::io;
+
+use colored::Colorize;
+
+use crate::{config::WorkflowsConfig, intergrations::sh, repo::Repo, variables::TemplateVariables};
+
+use super::{confirm, new::print_step_result};
+
+/// Runs the commands of every `[[on_clone]]` rule matching a freshly cloned project, stopping at
+/// the first command that fails
+///
+/// # Parameters
+///
+/// - `repo` The cloned project
+/// - `config` The user's config
+///
+/// # Returns
+///
+/// Whether the project should still be opened
+pub fn setup_cloned_project(repo: &Repo, config: &WorkflowsConfig) -> io::Result<bool> {
+    let project_root = match repo.get_project_root() {
+        Some(project_root) if project_root.is_dir() => project_root,
+        _ => return Ok(true),
+    };
+
+    let commands: Vec<String> = config
+        .on_clone()
+        .iter()
+        .filter(|rule| rule.matches(&repo.name(), &project_root))
+        .flat_map(|rule| rule.commands())
+        .collect();
+
+    if commands.is_empty() {
+        return Ok(true);
+    }
+
+    let projects_dir = repo.project_dir().unwrap_or_default();
+    let envs = TemplateVariables::new(&repo.name(), &projects_dir, &project_root).envs();
+
+    println!("Setting up {}\n", repo.name().bold());
+
+    for command in commands {
+        println!("{}", command.bold());
+
+        let outcome = sh::run_streamed(&command, &project_root, &envs)?;
+        print_step_result(outcome.success(), outcome.elapsed());
+
+        if !outcome.success() {
+            eprintln!(
+                "{} setup step failed, skipping the remaining steps: {}",
+                "ERROR".bright_red(),
+                command.bold()
+            );
+
+            return Ok(confirm("Open the project anyway?", true));
+        }
+    }
+
+    Ok(true)
+}
@@ -0,0 +1,230 @@
+//! This module contains the logic for the tag and fav commands
+
+use std::io;
+
+use colored::Colorize;
+
+use crate::{
+    config::WorkflowsConfig,
+    repo::Repo,
+    state::tags::{self, load_tags, ProjectId, Tags},
+};
+
+use super::{get_local_projects, state_or_default, update_state};
+
+/// Runs a tag subcommand
+///
+/// # Parameters
+///
+/// - `subcommand` The subcommand to run, either `add`, `remove` or `list`
+/// - `project_name` The name of the project to tag
+/// - `tag` The tag to add or remove. A leading `#` is ignored
+/// - `config` The user's config
+pub fn tag_command(
+    subcommand: Option<String>,
+    project_name: Option<String>,
+    tag: Option<String>,
+    config: WorkflowsConfig,
+) -> io::Result<()> {
+    let tag = tag.map(|tag| tag.trim_start_matches('#').to_string());
+
+    match (subcommand.as_deref(), project_name, tag) {
+        (Some("list"), _, _) => {
+            list_tags(&existing_tags(&config));
+            Ok(())
+        }
+        (Some("add"), Some(project_name), Some(tag)) => {
+            if !is_valid_tag(&tag) {
+                eprintln!(
+                    "{} tags can't be empty or contain whitespace",
+                    "ERROR".bright_red()
+                );
+                return Ok(());
+            }
+            let project = match find_project(&project_name, &config) {
+                Some(project) => project,
+                None => return Ok(()),
+            };
+
+            update_tags(|tags| match tags.add_tag(&project, &tag) {
+                true => println!("Tagged {} with #{}", project_name, tag),
+                false => println!("{} is already tagged with #{}", project_name, tag),
+            })
+        }
+        (Some("remove"), Some(project_name), Some(tag)) => {
+            let project = match find_project(&project_name, &config) {
+                Some(project) => project,
+                None => return Ok(()),
+            };
+
+            update_tags(|tags| match tags.remove_tag(&project, &tag) {
+                true => println!("Removed #{} from {}", tag, project_name),
+                false => println!("{} isn't tagged with #{}", project_name, tag),
+            })
+        }
+        _ => {
+            eprintln!("Usage: workflows tag <add <project> <tag>|remove <project> <tag>|list>");
+            Ok(())
+        }
+    }
+}
+
+/// Adds a project to the user's favourites, or removes it if it already is one. Without a
+/// project, the favourites are listed
+///
+/// # Parameters
+///
+/// - `project_name` The name of the project
+/// - `config` The user's config
+pub fn fav_command(project_name: Option<String>, config: WorkflowsConfig) -> io::Result<()> {
+    let project_name = match project_name {
+        Some(project_name) => project_name,
+        None => {
+            let tags = existing_tags(&config);
+
+            match tags.favourites().is_empty() {
+                true => println!("You don't have any favourite projects"),
+                false => tags
+                    .favourites()
+                    .iter()
+                    .for_each(|favourite| println!("{}", format_project(favourite))),
+            }
+
+            return Ok(());
+        }
+    };
+
+    let project = match find_project(&project_name, &config) {
+        Some(project) => project,
+        None => return Ok(()),
+    };
+
+    update_tags(|tags| match tags.toggle_favourite(&project) {
+        true => println!("Added {} to your favourites", project_name),
+        false => println!("Removed {} from your favourites", project_name),
+    })
+}
+
+/// Prints every tag in use, with the projects that have it
+///
+/// # Parameters
+///
+/// - `tags` The user's tags
+fn list_tags(tags: &Tags) {
+    let by_tag = tags.by_tag();
+
+    if by_tag.is_empty() {
+        println!("No projects have been tagged");
+        return;
+    }
+
+    for (tag, projects) in by_tag {
+        println!(
+            "{} {}",
+            format!("#{}", tag).bold(),
+            format!("({})", projects.len()).dimmed()
+        );
+        projects
+            .iter()
+            .for_each(|project| println!("{}", format_project(project)));
+        println!();
+    }
+}
+
+/// Formats a project for a list, with the projects directory it's in
+///
+/// # Parameters
+///
+/// - `project` The project
+pub(super) fn format_project(project: &ProjectId) -> String {
+    format!(
+        "• {} {}",
+        project.name(),
+        format!("({})", project.project_dir()).dimmed()
+    )
+}
+
+/// Loads the user's tags, without the tags and favourites of projects that no longer exist
+///
+/// # Parameters
+///
+/// - `config` The user's config
+pub(super) fn existing_tags(config: &WorkflowsConfig) -> Tags {
+    state_or_default(load_tags())
+        .retain_existing(&get_local_projects(config.general().projects_dirs()))
+}
+
+/// Loads the user's tags, lets them be changed, then saves them
+///
+/// # Parameters
+///
+/// - `update` The change to make
+fn update_tags(update: impl FnOnce(&mut Tags)) -> io::Result<()> {
+    let tags_file = tags::tags_file()
+        .ok_or_else(|| io::Error::other("there's no directory to store tags in"))?;
+
+    update_state(&tags_file, update)
+}
+
+/// Drops a removed project's tags and favourite, warning the user if they couldn't be
+///
+/// # Parameters
+///
+/// - `project` The project that was removed
+pub(super) fn forget_tags(project: &Repo) {
+    let forgotten = match tags::tags_file() {
+        Some(tags_file) if tags_file.exists() => update_state(&tags_file, |tags: &mut Tags| {
+            tags.forget(project);
+        }),
+        _ => Ok(()),
+    };
+
+    if let Err(err) = forgotten {
+        eprintln!(
+            "{} couldn't remove the tags of {}: {}",
+            "WARNING".bright_yellow(),
+            project.name().bold(),
+            err
+        );
+    }
+}
+
+/// Finds a local project by name, reporting it to the user if it doesn't exist
+///
+/// # Parameters
+///
+/// - `project_name` The name of the project
+/// - `config` The user's config
+fn find_project(project_name: &str, config: &WorkflowsConfig) -> Option<Repo> {
+    let project = get_local_projects(config.general().projects_dirs())
+        .into_iter()
+        .find(|project| project.name() == project_name);
+
+    if project.is_none() {
+        eprintln!("No project named {} could be found!", project_name.bold());
+    }
+
+    project
+}
+
+/// Whether a tag can be used. Tags can't contain whitespace, so they can be typed as `#tag`
+///
+/// # Parameters
+///
+/// - `tag` The tag, without its `#`
+fn is_valid_tag(tag: &str) -> bool {
+    !tag.is_empty() && !tag.chars().any(char::is_whitespace)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::is_valid_tag;
+
+    #[test]
+    fn is_valid_tag_works() {
+        assert!(is_valid_tag("client"));
+        assert!(is_valid_tag("client-work"));
+        assert!(!is_valid_tag(""));
+        assert!(!is_valid_tag("client work"));
+    }
+}
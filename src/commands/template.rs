@@ -0,0 +1,285 @@
+//! This module contains the logic for the template command
+
+use std::{
+    env, fs, io,
+    path::{Path, PathBuf},
+    process,
+};
+
+use colored::Colorize;
+
+use crate::{
+    config::{templates::WorkspaceTemplate, WorkflowsConfig},
+    intergrations::sh::format_elapsed,
+    variables::TemplateVariables,
+};
+
+use super::new::{run_template, TemplateResult};
+
+/// The name given to the throwaway project templates are tested in
+const TEST_PROJECT_NAME: &str = "template-test";
+
+/// A throwaway directory a template is tested in, which is removed when dropped so it's cleaned up
+/// however the test ends
+struct Sandbox(PathBuf);
+
+impl Sandbox {
+    /// Creates an empty sandbox, unique to this process
+    fn new() -> io::Result<Self> {
+        let path = env::temp_dir().join(format!("workflows-template-test-{}", process::id()));
+        fs::create_dir_all(&path)?;
+
+        Ok(Self(path))
+    }
+
+    /// The path to the sandbox
+    fn path(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for Sandbox {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
+/// Runs a template subcommand
+///
+/// # Parameters
+///
+/// - `subcommand` The subcommand to run, either `list`, `show` or `test`
+/// - `template_name` The name of the template the subcommand is for
+/// - `config` The user's config
+pub fn template_command(
+    subcommand: Option<String>,
+    template_name: Option<String>,
+    config: WorkflowsConfig,
+) -> io::Result<()> {
+    print_template_warnings(&config);
+
+    match (subcommand.as_deref(), template_name) {
+        (Some("list"), _) => list_templates(&config),
+        (Some("show"), Some(template_name)) => show_template(&template_name, &config),
+        (Some("test"), Some(template_name)) => test_template(&template_name, &config),
+        _ => {
+            eprintln!("Usage: workflows template <list|show <name>|test <name>>");
+            Ok(())
+        }
+    }
+}
+
+/// Prints any problems found while loading the user's templates
+///
+/// # Parameters
+///
+/// - `config` The user's config
+pub fn print_template_warnings(config: &WorkflowsConfig) {
+    for warning in config.template_warnings() {
+        eprintln!("{} {}", "WARNING".bright_yellow(), warning);
+    }
+}
+
+/// Lists the names of the user's templates
+///
+/// # Parameters
+///
+/// - `config` The user's config
+fn list_templates(config: &WorkflowsConfig) -> io::Result<()> {
+    let templates = config.templates();
+
+    if templates.is_empty() {
+        println!("No templates have been configured");
+        return Ok(());
+    }
+
+    templates.iter().for_each(|template| {
+        match template.extends().is_empty() {
+            true => println!("• {}", template.name()),
+            false => println!(
+                "• {} {}",
+                template.name(),
+                format!("(extends {})", template.extends().join(", ")).dimmed()
+            ),
+        };
+    });
+
+    Ok(())
+}
+
+/// Finds the template with the given name, reporting it to the user if it doesn't exist
+///
+/// # Parameters
+///
+/// - `template_name` The name of the template to find
+/// - `config` The user's config
+fn find_template(template_name: &str, config: &WorkflowsConfig) -> Option<WorkspaceTemplate> {
+    let template = config
+        .templates()
+        .into_iter()
+        .find(|template| template.name() == template_name);
+
+    if template.is_none() {
+        eprintln!("No template named {} could be found!", template_name.bold());
+    }
+
+    template
+}
+
+/// Shows a template with the templates it extends merged in
+///
+/// # Parameters
+///
+/// - `template_name` The name of the template to show
+/// - `config` The user's config
+fn show_template(template_name: &str, config: &WorkflowsConfig) -> io::Result<()> {
+    let template = match find_template(template_name, config) {
+        Some(template) => template,
+        None => return Ok(()),
+    };
+
+    let resolved = match template.resolve(&config.templates()) {
+        Ok(resolved) => resolved,
+        Err(err) => {
+            eprintln!("{} {}", "ERROR".bright_red(), err);
+            return Ok(());
+        }
+    };
+
+    println!("{}", resolved.name().bold());
+
+    if !resolved.extends().is_empty() {
+        println!("extends {}", resolved.extends().join(", "));
+    }
+    println!();
+
+    if !resolved.prompts().is_empty() {
+        println!("{}", "Prompts".bold());
+        resolved
+            .prompts()
+            .iter()
+            .for_each(|prompt| println!("• {} ({})", prompt.message().trim(), prompt.name()));
+        println!();
+    }
+
+    if !resolved.skeletons().is_empty() {
+        println!("{}", "Skeletons".bold());
+        resolved
+            .skeletons()
+            .iter()
+            .for_each(|skeleton| println!("• {}", skeleton.display()));
+        println!();
+    }
+
+    println!("{}", "Steps".bold());
+    resolved
+        .commands()
+        .iter()
+        .enumerate()
+        .for_each(|(i, step)| {
+            println!(
+                "{}. {}{}",
+                i + 1,
+                step.command(),
+                match step.continue_on_error() {
+                    true => " (continues on error)".dimmed().to_string(),
+                    false => String::new(),
+                }
+            )
+        });
+
+    Ok(())
+}
+
+/// Runs a template in a throwaway directory, reporting how each step went before cleaning up.
+/// Prompts are answered with their default answers, and the projects directory is the user's
+/// first, as in a real run. Ctrl-C stops the running step rather than workflows, so the sandbox
+/// is still cleaned up
+///
+/// # Parameters
+///
+/// - `template_name` The name of the template to test
+/// - `config` The user's config
+fn test_template(template_name: &str, config: &WorkflowsConfig) -> io::Result<()> {
+    let template = match find_template(template_name, config) {
+        Some(template) => template,
+        None => return Ok(()),
+    };
+
+    let resolved = match template.resolve(&config.templates()) {
+        Ok(resolved) => resolved,
+        Err(err) => {
+            eprintln!("{} {}", "ERROR".bright_red(), err);
+            return Ok(());
+        }
+    };
+
+    let sandbox = Sandbox::new()?;
+    let project_dir = sandbox.path().join(TEST_PROJECT_NAME);
+    fs::create_dir_all(&project_dir)?;
+
+    // The steps get the interrupt, failing the template, so the sandbox is dropped as usual
+    let _ = ctrlc::set_handler(|| {});
+
+    let projects_dir = config
+        .general()
+        .projects_dirs()
+        .first()
+        .cloned()
+        .unwrap_or_default();
+
+    let mut variables = TemplateVariables::new(TEST_PROJECT_NAME, &projects_dir, &project_dir);
+    for prompt in resolved.prompts() {
+        variables.insert(prompt.name(), prompt.default_answer());
+    }
+
+    println!(
+        "Testing {} in {}\n",
+        resolved.name().bold(),
+        project_dir.display()
+    );
+
+    let result = run_template(&resolved, &variables, &project_dir);
+
+    // Cleaning up before printing the summary, which doesn't need the project
+    drop(sandbox);
+
+    let (completed, reports) = match result? {
+        TemplateResult::Completed(reports) => (true, reports),
+        TemplateResult::Failed(reports) => (false, reports),
+    };
+
+    println!("{}", "Summary".bold());
+    for report in &reports {
+        println!(
+            "[{}] {:>8}  {:>7}  {}",
+            match report.success() {
+                true => "✓".bright_green().bold(),
+                false => "⨯".bright_red().bold(),
+            },
+            match report.code() {
+                Some(code) => format!("exit {}", code),
+                None => "-".to_string(),
+            },
+            format_elapsed(report.elapsed()),
+            report.command()
+        );
+
+        if let Some(error) = report.error() {
+            println!("    {}", error.dimmed());
+        }
+    }
+
+    let skipped = resolved.skeletons().len() + resolved.commands().len() - reports.len();
+    if skipped > 0 {
+        println!("Skipped {} remaining step(s)", skipped);
+    }
+
+    println!();
+    match completed {
+        true => println!("{} completed successfully", resolved.name()),
+        false => println!("{} failed", resolved.name()),
+    }
+
+    Ok(())
+}
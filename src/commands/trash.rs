@@ -0,0 +1,209 @@
+//! This module contains the logic for the trash command
+
+use std::{io, path::Path};
+
+use colored::Colorize;
+
+use crate::{
+    config::WorkflowsConfig,
+    intergrations::tmuxinator::tmuxinator_config_file,
+    repo::Repo,
+    state::trash::{self, TrashEntry},
+};
+
+use super::{confirm, format_age, history::forget_history, tags::forget_tags};
+
+/// Runs a trash subcommand
+///
+/// # Parameters
+///
+/// - `subcommand` The subcommand to run, either `list`, `restore` or `purge`
+/// - `project_name` The name of the project the subcommand is for
+/// - `config` The user's config
+pub fn trash_command(
+    subcommand: Option<String>,
+    project_name: Option<String>,
+    config: WorkflowsConfig,
+) -> io::Result<()> {
+    let trash_dir = match trash::trash_dir() {
+        Some(trash_dir) => trash_dir,
+        None => {
+            eprintln!(
+                "{} couldn't find a directory for the trash",
+                "ERROR".bright_red()
+            );
+            return Ok(());
+        }
+    };
+
+    purge_expired(&trash_dir, &config)?;
+
+    match (subcommand.as_deref(), project_name) {
+        (Some("list"), _) => list_trash(&trash_dir),
+        (Some("restore"), Some(project_name)) => restore_project(&trash_dir, &project_name),
+        (Some("purge"), project_name) => purge_trash(&trash_dir, project_name),
+        _ => {
+            eprintln!("Usage: workflows trash <list|restore <name>|purge [name]>");
+            Ok(())
+        }
+    }
+}
+
+/// Permanently deletes the projects that have been in the trash for longer than the user's
+/// retention period
+///
+/// # Parameters
+///
+/// - `trash_dir` The trash directory
+/// - `config` The user's config
+pub fn purge_expired(trash_dir: &Path, config: &WorkflowsConfig) -> io::Result<()> {
+    let retention_days = config.trash().retention_days();
+
+    for entry in trash::list(trash_dir) {
+        if entry.expired(retention_days) {
+            purge_entry(&entry)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Lists the projects in the trash
+///
+/// # Parameters
+///
+/// - `trash_dir` The trash directory
+fn list_trash(trash_dir: &Path) -> io::Result<()> {
+    let entries = trash::list(trash_dir);
+
+    if entries.is_empty() {
+        println!("The trash is empty");
+        return Ok(());
+    }
+
+    let width = entries
+        .iter()
+        .map(|entry| entry.name().len())
+        .max()
+        .unwrap_or_default();
+
+    for entry in entries {
+        println!(
+            "• {:width$}  {}  {}",
+            entry.name(),
+            entry.original_path().display(),
+            format!(
+                "deleted {}",
+                format_age(entry.deleted_at().elapsed().unwrap_or_default())
+            )
+            .dimmed()
+        );
+    }
+
+    Ok(())
+}
+
+/// Finds the most recently trashed project with the given name, reporting it to the user if it
+/// isn't in the trash
+///
+/// # Parameters
+///
+/// - `trash_dir` The trash directory
+/// - `project_name` The name of the project to find
+fn find_entry(trash_dir: &Path, project_name: &str) -> Option<TrashEntry> {
+    let entry = trash::list(trash_dir)
+        .into_iter()
+        .find(|entry| entry.name() == project_name);
+
+    if entry.is_none() {
+        eprintln!("No project named {} is in the trash!", project_name.bold());
+    }
+
+    entry
+}
+
+/// Restores the most recently trashed project with the given name to where it was
+///
+/// # Parameters
+///
+/// - `trash_dir` The trash directory
+/// - `project_name` The name of the project to restore
+fn restore_project(trash_dir: &Path, project_name: &str) -> io::Result<()> {
+    let entry = match find_entry(trash_dir, project_name) {
+        Some(entry) => entry,
+        None => return Ok(()),
+    };
+
+    let tmuxinator_file = tmuxinator_config_file(&Repo::new(entry.name(), true, None));
+
+    match trash::restore(&entry, &tmuxinator_file) {
+        Ok(()) => println!(
+            "Restored {} to {}",
+            entry.name(),
+            entry.original_path().display()
+        ),
+        Err(err) if err.kind() == io::ErrorKind::AlreadyExists => eprintln!(
+            "{} couldn't restore {}, as {}",
+            "ERROR".bright_red(),
+            entry.name().bold(),
+            err
+        ),
+        Err(err) => return Err(err),
+    }
+
+    Ok(())
+}
+
+/// Permanently deletes a project in the trash, or every project if no name is given
+///
+/// # Parameters
+///
+/// - `trash_dir` The trash directory
+/// - `project_name` The name of the project to delete
+fn purge_trash(trash_dir: &Path, project_name: Option<String>) -> io::Result<()> {
+    let entries = match project_name {
+        Some(project_name) => match find_entry(trash_dir, &project_name) {
+            Some(entry) => vec![entry],
+            None => return Ok(()),
+        },
+        None => trash::list(trash_dir),
+    };
+
+    if entries.is_empty() {
+        println!("The trash is empty");
+        return Ok(());
+    }
+
+    let prompt = match entries.len() {
+        1 => format!("Permanently delete {}?", entries[0].name()),
+        count => format!("Permanently delete the {} projects in the trash?", count),
+    };
+
+    if !confirm(prompt, false) {
+        return Ok(());
+    }
+
+    for entry in &entries {
+        purge_entry(entry)?;
+        println!("Deleted {}", entry.name());
+    }
+
+    Ok(())
+}
+
+/// Permanently deletes a project in the trash, dropping its tags and favourite now that it can't
+/// be restored
+///
+/// # Parameters
+///
+/// - `entry` The project to delete
+fn purge_entry(entry: &TrashEntry) -> io::Result<()> {
+    trash::purge(entry)?;
+
+    if let Some(project) = entry.project() {
+        forget_tags(&project);
+        forget_history(&project);
+    }
+
+    Ok(())
+}
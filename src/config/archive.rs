@@ -0,0 +1,120 @@
+//! This module contains the logic for archive configuration
+
+use std::path::PathBuf;
+
+use serde::Deserialize;
+
+use crate::state::data_dir;
+
+const DEFAULT_EXCLUDES: [&str; 2] = ["target", "node_modules"];
+const DEFAULT_SHOW_IN_PICKER: bool = false;
+const DEFAULT_INDICATOR: &str = "archived: ";
+
+#[derive(Debug, Deserialize, Default, Clone, PartialEq, Eq)]
+pub struct ArchiveConfig {
+    /// The directory archived projects are stored in
+    dir: Option<String>,
+
+    /// Directories and files left out of archives, such as build artefacts
+    excludes: Option<Vec<String>>,
+
+    /// Whether archived projects are listed when opening a project
+    show_in_picker: Option<bool>,
+
+    /// The indicator shown next to archived projects in fzf
+    indicator: Option<String>,
+}
+
+impl ArchiveConfig {
+    /// The directory archived projects are stored in. Relative paths are relative to the home
+    /// directory, like `projects_dirs`
+    ///
+    /// Default: `~/.local/share/workflows/archive/` on Linux
+    pub fn dir(&self) -> Option<PathBuf> {
+        let dir = match self.dir.clone() {
+            Some(dir) => dir,
+            None => return Some(data_dir()?.join("archive")),
+        };
+
+        let dir = dir.strip_prefix("~/").unwrap_or(&dir);
+        Some(dirs::home_dir()?.join(dir))
+    }
+
+    /// Names of directories and files left out of archives, matched at any depth. These should be
+    /// things that can be rebuilt, such as build artefacts and dependencies
+    ///
+    /// Default: `["target", "node_modules"]`
+    pub fn excludes(&self) -> Vec<String> {
+        self.excludes.clone().unwrap_or(
+            DEFAULT_EXCLUDES
+                .iter()
+                .map(|exclude| exclude.to_string())
+                .collect(),
+        )
+    }
+
+    /// Whether archived projects are listed when opening a project. Opening one unarchives it
+    ///
+    /// Default: `false`
+    pub fn show_in_picker(&self) -> bool {
+        self.show_in_picker.unwrap_or(DEFAULT_SHOW_IN_PICKER)
+    }
+
+    /// The indicator shown next to archived projects in fzf
+    ///
+    /// Default: `"archived: "`
+    pub fn indicator(&self) -> String {
+        self.indicator
+            .clone()
+            .unwrap_or(DEFAULT_INDICATOR.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::{
+        archive::{DEFAULT_EXCLUDES, DEFAULT_INDICATOR, DEFAULT_SHOW_IN_PICKER},
+        WorkflowsConfig,
+    };
+    use crate::state::data_dir;
+
+    #[test]
+    fn archive_works() {
+        let toml = "\
+                    [archive]\n\
+                    dir = '~/Archive'\n\
+                    excludes = ['dist']\n\
+                    show_in_picker = true\n\
+                    indicator = 'old: '";
+
+        let config: WorkflowsConfig = toml::from_str(toml).unwrap();
+
+        assert_eq!(
+            config.archive().dir(),
+            Some(dirs::home_dir().unwrap().join("Archive"))
+        );
+        assert_eq!(config.archive().excludes(), vec!["dist"]);
+        assert!(config.archive().show_in_picker());
+        assert_eq!(config.archive().indicator(), "old: ");
+    }
+
+    #[test]
+    fn default_archive_works() {
+        let toml = "[archive]";
+
+        let config: WorkflowsConfig = toml::from_str(toml).unwrap();
+
+        assert_eq!(config.archive.clone().unwrap().dir, None);
+        assert_eq!(config.archive.clone().unwrap().excludes, None);
+        assert_eq!(config.archive.clone().unwrap().show_in_picker, None);
+        assert_eq!(config.archive.clone().unwrap().indicator, None);
+
+        assert_eq!(
+            config.archive().dir(),
+            Some(data_dir().unwrap().join("archive"))
+        );
+        assert_eq!(config.archive().excludes(), DEFAULT_EXCLUDES);
+        assert_eq!(config.archive().show_in_picker(), DEFAULT_SHOW_IN_PICKER);
+        assert_eq!(config.archive().indicator(), DEFAULT_INDICATOR);
+    }
+}
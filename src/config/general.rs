@@ -1,4 +1,6 @@
-use serde::Deserialize;
+use std::collections::BTreeMap;
+
+use serde::{de::Error, Deserialize, Deserializer};
 
 /// The default location for projects to be stored, ~/Projects/
 const DEFAULT_PROJECTS_DIR: &str = "Projects/";
@@ -11,6 +13,40 @@ pub struct GeneralConfig {
 
     /// Whether projects should be opened after they're created
     open_new_projects: Option<bool>,
+
+    /// The template to use for new projects, instead of prompting for one
+    default_template: Option<String>,
+
+    /// The template to use for new projects in a specific projects directory, keyed by the
+    /// directory without its trailing `/`
+    #[serde(default, deserialize_with = "deserialize_projects_dir_templates")]
+    projects_dir_templates: Option<BTreeMap<String, String>>,
+}
+
+/// Reads `projects_dir_templates`, trimming the trailing `/` from each directory. Directories that
+/// are the same once trimmed, e.g. `Work` and `Work/`, are rejected, as it wouldn't be clear which
+/// template to use
+fn deserialize_projects_dir_templates<'de, D>(
+    deserializer: D,
+) -> Result<Option<BTreeMap<String, String>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let templates = BTreeMap::<String, String>::deserialize(deserializer)?;
+
+    let mut normalised = BTreeMap::new();
+    for (dir, template) in templates {
+        let key = dir.trim_end_matches('/').to_string();
+
+        if normalised.insert(key.clone(), template).is_some() {
+            return Err(D::Error::custom(format!(
+                "{} is given more than one template in projects_dir_templates",
+                key
+            )));
+        }
+    }
+
+    Ok(Some(normalised))
 }
 
 impl GeneralConfig {
@@ -25,6 +61,27 @@ impl GeneralConfig {
     pub fn open_new_projects(&self) -> bool {
         self.open_new_projects.unwrap_or(DEFAULT_OPEN_NEW_PROJECTS)
     }
+
+    /// The template to use for new projects, instead of prompting for one
+    ///
+    /// Default: `None`
+    pub fn default_template(&self) -> Option<String> {
+        self.default_template.clone()
+    }
+
+    /// The template to use for new projects created in the given projects directory. Falls back
+    /// to the default template if the directory doesn't have one
+    ///
+    /// # Parameters
+    ///
+    /// - `projects_dir` The projects directory the project is being created in
+    pub fn default_template_for(&self, projects_dir: &str) -> Option<String> {
+        self.projects_dir_templates
+            .as_ref()
+            .and_then(|templates| templates.get(projects_dir.trim_end_matches('/')))
+            .cloned()
+            .or(self.default_template())
+    }
 }
 
 #[cfg(test)]
@@ -100,4 +157,69 @@ open_new_projects = false";
             DEFAULT_OPEN_NEW_PROJECTS
         )
     }
+
+    #[test]
+    fn default_template_works() {
+        let toml = "\
+[general]
+default_template = 'rust'";
+
+        let config: WorkflowsConfig = toml::from_str(toml).expect("Failed to unwrap toml");
+
+        assert_eq!(
+            config.general().default_template(),
+            Some("rust".to_string())
+        )
+    }
+
+    #[test]
+    fn default_default_template_works() {
+        let toml = "[general]";
+
+        let config: WorkflowsConfig = toml::from_str(toml).expect("Failed to unwrap toml");
+
+        assert_eq!(config.general().default_template(), None);
+        assert_eq!(config.general().default_template_for("Projects/"), None);
+    }
+
+    #[test]
+    fn projects_dir_templates_works() {
+        let toml = "\
+[general]
+projects_dirs = ['Projects/', 'Work/', 'Scratch/']
+default_template = 'rust'
+
+[general.projects_dir_templates]
+'Work/' = 'go'
+'Scratch' = 'blank'";
+
+        let config: WorkflowsConfig = toml::from_str(toml).expect("Failed to unwrap toml");
+
+        assert_eq!(
+            config.general().default_template_for("Work/"),
+            Some("go".to_string())
+        );
+        assert_eq!(
+            config.general().default_template_for("Scratch/"),
+            Some("blank".to_string())
+        );
+        assert_eq!(
+            config.general().default_template_for("Projects/"),
+            Some("rust".to_string())
+        );
+    }
+
+    #[test]
+    fn projects_dir_templates_rejects_duplicates() {
+        let toml = "\
+[general.projects_dir_templates]
+'Work' = 'go'
+'Work/' = 'rust'";
+
+        let err = toml::from_str::<WorkflowsConfig>(toml).unwrap_err();
+
+        assert!(err
+            .to_string()
+            .contains("Work is given more than one template"));
+    }
 }
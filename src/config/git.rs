@@ -4,6 +4,10 @@ use serde::Deserialize;
 
 const DEFAULT_CHECK_TREE: bool = true;
 const DEFAULT_CHECK_PUSH: bool = true;
+const DEFAULT_PRECIOUS_FILES: [&str; 2] = [".env", ".env.*"];
+const DEFAULT_FETCH_BEFORE_CHECK: bool = false;
+const DEFAULT_FETCH_TIMEOUT: u64 = 10;
+const DEFAULT_BACKUP_UNPUSHED: bool = true;
 
 #[derive(Debug, Deserialize, Default, Clone, PartialEq, Eq)]
 pub struct GitConfig {
@@ -12,6 +16,18 @@ pub struct GitConfig {
 
     /// Whether to check the push status of the repo  before deleting
     check_push: Option<bool>,
+
+    /// Whether to fetch the repo's remotes before checking its push status
+    fetch_before_check: Option<bool>,
+
+    /// How many seconds to wait for the fetch before giving up
+    fetch_timeout: Option<u64>,
+
+    /// Globs matching ignored files that should be reported before deleting
+    precious_files: Option<Vec<String>>,
+
+    /// Whether to back up unpushed commits and uncommitted changes before deleting
+    backup_unpushed: Option<bool>,
 }
 
 impl GitConfig {
@@ -24,20 +40,64 @@ impl GitConfig {
         self.check_tree.unwrap_or(DEFAULT_CHECK_TREE)
     }
 
-    /// Whether to check the push status of the repo  before deleting
+    /// Whether to check the push status of the repo  before deleting. Branches are compared
+    /// against the remote-tracking refs from the last fetch
     ///
-    /// Peformance cost is noticable, as it involves networks.
+    /// Peformance cost is neglagible, unless `fetch_before_check` is enabled
     ///
     /// Default: `true`
     pub fn check_push(&self) -> bool {
         self.check_push.unwrap_or(DEFAULT_CHECK_PUSH)
     }
+
+    /// Whether to fetch the repo's remotes before checking its push status, so the
+    /// remote-tracking refs are up to date
+    ///
+    /// Peformance cost is noticable, as it involves networks.
+    ///
+    /// Default: `false`
+    pub fn fetch_before_check(&self) -> bool {
+        self.fetch_before_check
+            .unwrap_or(DEFAULT_FETCH_BEFORE_CHECK)
+    }
+
+    /// How many seconds to wait for the fetch before checking the push status without it
+    ///
+    /// Default: `10`
+    pub fn fetch_timeout(&self) -> u64 {
+        self.fetch_timeout.unwrap_or(DEFAULT_FETCH_TIMEOUT)
+    }
+
+    /// Globs matching ignored files that should be reported before deleting, as they can't be
+    /// recovered from the remote, e.g. `.env`
+    ///
+    /// Default: `[".env", ".env.*"]`
+    pub fn precious_files(&self) -> Vec<String> {
+        self.precious_files.clone().unwrap_or(
+            DEFAULT_PRECIOUS_FILES
+                .iter()
+                .map(|file| file.to_string())
+                .collect(),
+        )
+    }
+
+    /// Whether to write a git bundle of every ref, and a patch of uncommitted changes, to the
+    /// backups directory before deleting a repo the checks found unpushed commits or a dirty
+    /// tree in
+    ///
+    /// Default: `true`
+    pub fn backup_unpushed(&self) -> bool {
+        self.backup_unpushed.unwrap_or(DEFAULT_BACKUP_UNPUSHED)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use crate::config::{
-        git::{DEFAULT_CHECK_PUSH, DEFAULT_CHECK_TREE},
+        git::{
+            DEFAULT_BACKUP_UNPUSHED, DEFAULT_CHECK_PUSH, DEFAULT_CHECK_TREE,
+            DEFAULT_FETCH_BEFORE_CHECK, DEFAULT_FETCH_TIMEOUT, DEFAULT_PRECIOUS_FILES,
+        },
         WorkflowsConfig,
     };
 
@@ -84,4 +144,77 @@ mod tests {
 
         assert_eq!(config.git().check_push(), DEFAULT_CHECK_PUSH);
     }
+
+    #[test]
+    fn precious_files_works() {
+        let toml = "\
+                    [git]\n\
+                    precious_files = [\"*.pem\"]";
+
+        let config: WorkflowsConfig = toml::from_str(toml).unwrap();
+
+        assert_eq!(config.git().precious_files(), vec!["*.pem"]);
+    }
+
+    #[test]
+    fn default_precious_files_works() {
+        let toml = "[git]";
+
+        let config: WorkflowsConfig = toml::from_str(toml).unwrap();
+
+        assert_eq!(config.git.clone().unwrap().precious_files, None);
+
+        assert_eq!(config.git().precious_files(), DEFAULT_PRECIOUS_FILES);
+    }
+
+    #[test]
+    fn fetch_before_check_works() {
+        let toml = "\
+                    [git]\n\
+                    fetch_before_check = true\n\
+                    fetch_timeout = 3";
+
+        let config: WorkflowsConfig = toml::from_str(toml).unwrap();
+
+        assert!(config.git().fetch_before_check());
+        assert_eq!(config.git().fetch_timeout(), 3);
+    }
+
+    #[test]
+    fn default_fetch_before_check_works() {
+        let toml = "[git]";
+
+        let config: WorkflowsConfig = toml::from_str(toml).unwrap();
+
+        assert_eq!(config.git.clone().unwrap().fetch_before_check, None);
+        assert_eq!(config.git.clone().unwrap().fetch_timeout, None);
+
+        assert_eq!(
+            config.git().fetch_before_check(),
+            DEFAULT_FETCH_BEFORE_CHECK
+        );
+        assert_eq!(config.git().fetch_timeout(), DEFAULT_FETCH_TIMEOUT);
+    }
+
+    #[test]
+    fn backup_unpushed_works() {
+        let toml = "\
+                    [git]\n\
+                    backup_unpushed = false";
+
+        let config: WorkflowsConfig = toml::from_str(toml).unwrap();
+
+        assert!(!config.git().backup_unpushed());
+    }
+
+    #[test]
+    fn default_backup_unpushed_works() {
+        let toml = "[git]";
+
+        let config: WorkflowsConfig = toml::from_str(toml).unwrap();
+
+        assert_eq!(config.git.clone().unwrap().backup_unpushed, None);
+
+        assert_eq!(config.git().backup_unpushed(), DEFAULT_BACKUP_UNPUSHED);
+    }
 }
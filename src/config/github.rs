@@ -5,6 +5,8 @@ use serde::Deserialize;
 const DEFAULT_ENABLED: bool = true;
 const DEFAULT_CONFIRM_CLONING: bool = true;
 const DEFAULT_PROJECT_INDICATOR: &str = " ";
+const DEFAULT_REPO_VISIBILITY: &str = "private";
+const DEFAULT_PUBLISH: bool = false;
 
 #[derive(Debug, Deserialize, Default, Clone, PartialEq, Eq)]
 pub struct GithubConfig {
@@ -20,6 +22,21 @@ pub struct GithubConfig {
 
     /// The indicator shown next to non-local projects in fzf
     project_indicator: Option<String>,
+
+    /// The users and organisations whose template repos can be used to create projects
+    ///
+    /// Default: `[]`
+    template_owners: Option<Vec<String>>,
+
+    /// The visibility of repos created on GitHub, either `public`, `private` or `internal`
+    ///
+    /// Default: `private`
+    repo_visibility: Option<String>,
+
+    /// Whether new projects should be published to GitHub after they're created
+    ///
+    /// Default: `false`
+    publish: Option<bool>,
 }
 
 impl GithubConfig {
@@ -42,12 +59,38 @@ impl GithubConfig {
             .clone()
             .unwrap_or(DEFAULT_PROJECT_INDICATOR.to_string())
     }
+
+    /// The users and organisations whose template repos can be used to create projects
+    ///
+    /// Default: `[]`
+    pub fn template_owners(&self) -> Vec<String> {
+        self.template_owners.clone().unwrap_or_default()
+    }
+
+    /// The visibility of repos created on GitHub, either `public`, `private` or `internal`
+    ///
+    /// Default: `private`
+    pub fn repo_visibility(&self) -> String {
+        self.repo_visibility
+            .clone()
+            .unwrap_or(DEFAULT_REPO_VISIBILITY.to_string())
+    }
+
+    /// Whether new projects should be published to GitHub after they're created
+    ///
+    /// Default: `false`
+    pub fn publish(&self) -> bool {
+        self.publish.unwrap_or(DEFAULT_PUBLISH)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use crate::config::{
-        github::{DEFAULT_CONFIRM_CLONING, DEFAULT_ENABLED, DEFAULT_PROJECT_INDICATOR},
+        github::{
+            DEFAULT_CONFIRM_CLONING, DEFAULT_ENABLED, DEFAULT_PROJECT_INDICATOR, DEFAULT_PUBLISH,
+            DEFAULT_REPO_VISIBILITY,
+        },
         WorkflowsConfig,
     };
 
@@ -119,4 +162,73 @@ mod tests {
             DEFAULT_PROJECT_INDICATOR
         );
     }
+
+    #[test]
+    fn template_owners_works() {
+        let toml = "\
+                    [github]\n\
+                    template_owners = ['danielronalds', 'my-org']";
+
+        let config: WorkflowsConfig = toml::from_str(toml).unwrap();
+
+        assert_eq!(
+            config.github().template_owners,
+            Some(vec!["danielronalds".to_string(), "my-org".to_string()])
+        );
+    }
+
+    #[test]
+    fn default_template_owners_works() {
+        let toml = "[github]";
+
+        let config: WorkflowsConfig = toml::from_str(toml).unwrap();
+
+        assert_eq!(config.github.clone().unwrap().template_owners, None);
+
+        assert!(config.github().template_owners().is_empty());
+    }
+
+    #[test]
+    fn repo_visibility_works() {
+        let toml = "\
+                    [github]\n\
+                    repo_visibility = 'public'";
+
+        let config: WorkflowsConfig = toml::from_str(toml).unwrap();
+
+        assert_eq!(config.github().repo_visibility, Some("public".into()));
+    }
+
+    #[test]
+    fn default_repo_visibility_works() {
+        let toml = "[github]";
+
+        let config: WorkflowsConfig = toml::from_str(toml).unwrap();
+
+        assert_eq!(config.github.clone().unwrap().repo_visibility, None);
+
+        assert_eq!(config.github().repo_visibility(), DEFAULT_REPO_VISIBILITY);
+    }
+
+    #[test]
+    fn publish_works() {
+        let toml = "\
+                    [github]\n\
+                    publish = true";
+
+        let config: WorkflowsConfig = toml::from_str(toml).unwrap();
+
+        assert_eq!(config.github().publish, Some(true));
+    }
+
+    #[test]
+    fn default_publish_works() {
+        let toml = "[github]";
+
+        let config: WorkflowsConfig = toml::from_str(toml).unwrap();
+
+        assert_eq!(config.github.clone().unwrap().publish, None);
+
+        assert_eq!(config.github().publish(), DEFAULT_PUBLISH);
+    }
 }
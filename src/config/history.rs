@@ -0,0 +1,62 @@
+//! This module contains the logic for history configuration
+
+use serde::Deserialize;
+
+const DEFAULT_ENABLED: bool = true;
+
+#[derive(Debug, Deserialize, Default, Clone, PartialEq, Eq)]
+pub struct HistoryConfig {
+    /// Whether opened projects are recorded, and used to order the picker
+    enabled: Option<bool>,
+
+    /// Projects that are always listed first in the picker
+    pinned: Option<Vec<String>>,
+}
+
+impl HistoryConfig {
+    /// Whether opened projects are recorded, and used to order the picker by frecency
+    ///
+    /// Default: `true`
+    pub fn enabled(&self) -> bool {
+        self.enabled.unwrap_or(DEFAULT_ENABLED)
+    }
+
+    /// The projects that are always listed first in the picker, in this order. A name pins the
+    /// project in every projects directory, and `dir/name`, e.g. `Work/api`, pins just one
+    ///
+    /// Default: `[]`
+    pub fn pinned(&self) -> Vec<String> {
+        self.pinned.clone().unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::{history::DEFAULT_ENABLED, WorkflowsConfig};
+
+    #[test]
+    fn history_works() {
+        let toml = "\
+                    [history]\n\
+                    enabled = false\n\
+                    pinned = ['workflows', 'dotfiles']";
+
+        let config: WorkflowsConfig = toml::from_str(toml).unwrap();
+
+        assert!(!config.history().enabled());
+        assert_eq!(config.history().pinned(), vec!["workflows", "dotfiles"]);
+    }
+
+    #[test]
+    fn default_history_works() {
+        let toml = "[history]";
+
+        let config: WorkflowsConfig = toml::from_str(toml).unwrap();
+
+        assert_eq!(config.history.clone().unwrap().enabled, None);
+        assert_eq!(config.history.clone().unwrap().pinned, None);
+
+        assert_eq!(config.history().enabled(), DEFAULT_ENABLED);
+        assert!(config.history().pinned().is_empty());
+    }
+}
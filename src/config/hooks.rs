@@ -0,0 +1,131 @@
+//! This module contains the logic for hooks configuration
+
+use serde::Deserialize;
+
+/// The points in a project's lifecycle that a hook can be run at
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Hook {
+    PreOpen,
+    PostOpen,
+    PreCreate,
+    PostCreate,
+    PreClone,
+    PostClone,
+    PreBorrow,
+    PostBorrow,
+    PreDelete,
+    PostDelete,
+}
+
+impl Hook {
+    /// The name of the hook, as it appears in the config
+    pub fn name(&self) -> &'static str {
+        match self {
+            Hook::PreOpen => "pre_open",
+            Hook::PostOpen => "post_open",
+            Hook::PreCreate => "pre_create",
+            Hook::PostCreate => "post_create",
+            Hook::PreClone => "pre_clone",
+            Hook::PostClone => "post_clone",
+            Hook::PreBorrow => "pre_borrow",
+            Hook::PostBorrow => "post_borrow",
+            Hook::PreDelete => "pre_delete",
+            Hook::PostDelete => "post_delete",
+        }
+    }
+
+    /// Whether the hook runs before its action, and so can abort it
+    pub fn is_pre(&self) -> bool {
+        matches!(
+            self,
+            Hook::PreOpen | Hook::PreCreate | Hook::PreClone | Hook::PreBorrow | Hook::PreDelete
+        )
+    }
+}
+
+#[derive(Debug, Deserialize, Default, Clone, PartialEq, Eq)]
+pub struct HooksConfig {
+    /// Run before a project is opened
+    pre_open: Option<String>,
+    /// Run after a project's session is opened. Outside of tmux, this is once the user detaches
+    /// from the session
+    post_open: Option<String>,
+    /// Run before a project is created
+    pre_create: Option<String>,
+    /// Run after a project is created
+    post_create: Option<String>,
+    /// Run before a project is cloned
+    pre_clone: Option<String>,
+    /// Run after a project is cloned
+    post_clone: Option<String>,
+    /// Run before a project is borrowed
+    pre_borrow: Option<String>,
+    /// Run after a borrowed project's session ends
+    post_borrow: Option<String>,
+    /// Run before a project is deleted
+    pre_delete: Option<String>,
+    /// Run after a project is deleted
+    post_delete: Option<String>,
+}
+
+impl HooksConfig {
+    /// The shell command to run for the given hook
+    ///
+    /// Default: `None`
+    pub fn command(&self, hook: Hook) -> Option<String> {
+        match hook {
+            Hook::PreOpen => self.pre_open.clone(),
+            Hook::PostOpen => self.post_open.clone(),
+            Hook::PreCreate => self.pre_create.clone(),
+            Hook::PostCreate => self.post_create.clone(),
+            Hook::PreClone => self.pre_clone.clone(),
+            Hook::PostClone => self.post_clone.clone(),
+            Hook::PreBorrow => self.pre_borrow.clone(),
+            Hook::PostBorrow => self.post_borrow.clone(),
+            Hook::PreDelete => self.pre_delete.clone(),
+            Hook::PostDelete => self.post_delete.clone(),
+        }
+        .filter(|command| !command.trim().is_empty())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::{hooks::Hook, WorkflowsConfig};
+
+    #[test]
+    fn hooks_works() {
+        let toml = "\
+                    [hooks]\n\
+                    pre_delete = \"./check-protected.sh\"\n\
+                    post_create = \"echo created\"\n\
+                    post_open = \"\"";
+
+        let config: WorkflowsConfig = toml::from_str(toml).unwrap();
+
+        assert_eq!(
+            config.hooks().command(Hook::PreDelete),
+            Some("./check-protected.sh".to_string())
+        );
+        assert_eq!(
+            config.hooks().command(Hook::PostCreate),
+            Some("echo created".to_string())
+        );
+        assert_eq!(config.hooks().command(Hook::PostOpen), None);
+    }
+
+    #[test]
+    fn default_hooks_works() {
+        let config: WorkflowsConfig = toml::from_str("").unwrap();
+
+        assert_eq!(config.hooks().command(Hook::PreOpen), None);
+        assert_eq!(config.hooks().command(Hook::PostDelete), None);
+    }
+
+    #[test]
+    fn is_pre_works() {
+        assert!(Hook::PreClone.is_pre());
+        assert!(!Hook::PostClone.is_pre());
+        assert_eq!(Hook::PreBorrow.name(), "pre_borrow");
+    }
+}
@@ -1,6 +1,7 @@
 use std::fs;
 use std::path::PathBuf;
 
+use colored::Colorize;
 use serde::Deserialize;
 
 pub mod general;
@@ -19,7 +20,22 @@ pub mod git;
 use git::GitConfig;
 
 pub mod templates;
-use templates::WorkspaceTemplate;
+
+pub mod on_clone;
+use on_clone::OnCloneRule;
+
+pub mod hooks;
+use hooks::HooksConfig;
+
+pub mod trash;
+
+pub mod archive;
+use archive::ArchiveConfig;
+
+pub mod history;
+use history::HistoryConfig;
+use templates::{read_template_files, templates_dir, WorkspaceTemplate};
+use trash::TrashConfig;
 
 /// Attempt to read the config file located at either of the following two paths:
 ///
@@ -32,14 +48,40 @@ use templates::WorkspaceTemplate;
 pub fn get_config() -> Option<WorkflowsConfig> {
     let home_config_file = dirs::home_dir()?.join(".workflows.toml");
     if home_config_file.is_file() {
-        return WorkflowsConfig::try_from(home_config_file).ok();
+        return read_config(home_config_file);
     }
 
     // If the config is not located in ~/.workflows.toml, then it might be in
     // ~/.config/workflows/config.toml
     let config_dir_file = dirs::config_dir()?.join("workflows/").join("config.toml");
 
-    WorkflowsConfig::try_from(config_dir_file).ok()
+    if !config_dir_file.is_file() {
+        return None;
+    }
+
+    read_config(config_dir_file)
+}
+
+/// Reads a config file, warning the user if it can't be parsed
+///
+/// # Parameters
+///
+/// - `path` The path to the config file
+fn read_config(path: PathBuf) -> Option<WorkflowsConfig> {
+    let display = path.display().to_string();
+
+    match WorkflowsConfig::try_from(path) {
+        Ok(config) => Some(config),
+        Err(err) => {
+            eprintln!(
+                "{} couldn't load {}, using the default config\n{}",
+                "WARNING".bright_yellow(),
+                display,
+                err.trim_end()
+            );
+            None
+        }
+    }
 }
 
 /// This struct represents the user's configuration
@@ -52,6 +94,15 @@ pub struct WorkflowsConfig {
     git: Option<GitConfig>,
     tmuxinator: Option<TmuxinatorConfig>,
     fzf: Option<FzfConfig>,
+    on_clone: Option<Vec<OnCloneRule>>,
+    hooks: Option<HooksConfig>,
+    trash: Option<TrashConfig>,
+    archive: Option<ArchiveConfig>,
+    history: Option<HistoryConfig>,
+
+    /// Problems found while loading templates from the templates directory
+    #[serde(skip)]
+    template_warnings: Vec<String>,
 }
 
 impl WorkflowsConfig {
@@ -65,6 +116,53 @@ impl WorkflowsConfig {
         self.template.clone().unwrap_or_default()
     }
 
+    /// Problems found while loading templates from the templates directory, such as a template
+    /// with the same name as another
+    pub fn template_warnings(&self) -> &[String] {
+        self.template_warnings.as_ref()
+    }
+
+    /// Adds the templates stored as files in ~/.config/workflows/templates/ to the inline
+    /// templates
+    pub fn with_template_files(mut self) -> Self {
+        if let Some(dir) = templates_dir() {
+            self.add_templates(read_template_files(&dir));
+        }
+        self
+    }
+
+    /// Adds templates to the config. Templates with the same name as an existing template are
+    /// skipped and reported as a warning, as are templates that couldn't be read
+    ///
+    /// # Parameters
+    ///
+    /// - `templates` The templates to add
+    fn add_templates(&mut self, templates: Vec<Result<WorkspaceTemplate, String>>) {
+        let mut existing = self.templates();
+
+        for template in templates {
+            let template = match template {
+                Ok(template) => template,
+                Err(warning) => {
+                    self.template_warnings.push(warning);
+                    continue;
+                }
+            };
+
+            if existing.iter().any(|x| x.name() == template.name()) {
+                self.template_warnings.push(format!(
+                    "A template named {} already exists, skipping the one in the templates directory",
+                    template.name()
+                ));
+                continue;
+            }
+
+            existing.push(template);
+        }
+
+        self.template = Some(existing);
+    }
+
     /// Returns the [`FzfConfig`] preferences in the config
     pub fn fzf(&self) -> FzfConfig {
         self.fzf.clone().unwrap_or_default()
@@ -80,6 +178,37 @@ impl WorkflowsConfig {
         self.github.clone().unwrap_or_default()
     }
 
+    /// Returns the [`OnCloneRule`]s run after a project is cloned
+    pub fn on_clone(&self) -> Vec<OnCloneRule> {
+        self.on_clone.clone().unwrap_or_default()
+    }
+
+    /// Disables the rules run after a project is cloned, for when the user opts out of them
+    pub fn without_clone_setup(mut self) -> Self {
+        self.on_clone = None;
+        self
+    }
+
+    /// Returns the [`HooksConfig`] in the config
+    pub fn hooks(&self) -> HooksConfig {
+        self.hooks.clone().unwrap_or_default()
+    }
+
+    /// Returns the [`TrashConfig`] preferences in the config
+    pub fn trash(&self) -> TrashConfig {
+        self.trash.clone().unwrap_or_default()
+    }
+
+    /// Returns the [`ArchiveConfig`] preferences in the config
+    pub fn archive(&self) -> ArchiveConfig {
+        self.archive.clone().unwrap_or_default()
+    }
+
+    /// Returns the [`HistoryConfig`] preferences in the config
+    pub fn history(&self) -> HistoryConfig {
+        self.history.clone().unwrap_or_default()
+    }
+
     /// Returns the [`TmuxinatorConfig`] preferences in the config
     pub fn tmuxinator(&self) -> TmuxinatorConfig {
         self.tmuxinator.clone().unwrap_or_default()
@@ -87,17 +216,69 @@ impl WorkflowsConfig {
 }
 
 impl TryFrom<PathBuf> for WorkflowsConfig {
-    type Error = &'static str;
+    type Error = String;
 
     fn try_from(value: PathBuf) -> Result<Self, Self::Error> {
         let toml_string = match fs::read_to_string(value) {
             Ok(toml) => toml,
-            Err(_) => return Err("Couldn't read string"),
+            Err(err) => return Err(err.to_string()),
         };
 
         match toml::from_str(&toml_string) {
             Ok(config) => Ok(config),
-            Err(_) => Err("Couldn't parse config from toml"),
+            Err(err) => Err(err.to_string()),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::config::WorkflowsConfig;
+
+    #[test]
+    fn add_templates_reports_conflicts() {
+        let toml = "\
+[[template]]
+name = 'rust'
+commands = ['cargo init']";
+
+        let mut config: WorkflowsConfig = toml::from_str(toml).unwrap();
+
+        let files: WorkflowsConfig = toml::from_str(
+            "\
+[[template]]
+name = 'rust'
+commands = ['cargo new']
+
+[[template]]
+name = 'go'",
+        )
+        .unwrap();
+
+        config.add_templates(
+            files
+                .templates()
+                .into_iter()
+                .map(Ok)
+                .chain([Err("Couldn't parse template".to_string())])
+                .collect(),
+        );
+
+        let names: Vec<String> = config
+            .templates()
+            .iter()
+            .map(|x| x.name().to_string())
+            .collect();
+
+        assert_eq!(names, vec!["rust", "go"]);
+        assert_eq!(config.templates()[0].commands()[0].command(), "cargo init");
+        assert_eq!(
+            config.template_warnings(),
+            vec![
+                "A template named rust already exists, skipping the one in the templates directory"
+                    .to_string(),
+                "Couldn't parse template".to_string()
+            ]
+        );
+    }
+}
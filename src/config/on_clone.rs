@@ -0,0 +1,121 @@
+//! This module contains the logic for the setup rules run after a project is cloned
+
+use std::path::Path;
+
+use serde::Deserialize;
+
+use crate::glob;
+
+/// A rule describing the commands to run in a project after it's cloned, such as installing
+/// dependencies
+#[derive(Debug, Deserialize, Default, Clone, PartialEq, Eq)]
+pub struct OnCloneRule {
+    /// Files in the project root, any of which must exist for the rule to match
+    files: Option<Vec<String>>,
+
+    /// Globs, any of which must match the project's name for the rule to match
+    names: Option<Vec<String>>,
+
+    /// The commands to run in the project root
+    commands: Option<Vec<String>>,
+}
+
+impl OnCloneRule {
+    /// Files in the project root, any of which must exist for the rule to match, e.g.
+    /// `Cargo.toml`
+    ///
+    /// Default: `[]`
+    pub fn files(&self) -> Vec<String> {
+        self.files.clone().unwrap_or_default()
+    }
+
+    /// Globs, any of which must match the project's name for the rule to match, e.g. `*-api`
+    ///
+    /// Default: `[]`
+    pub fn names(&self) -> Vec<String> {
+        self.names.clone().unwrap_or_default()
+    }
+
+    /// The commands to run in the project root
+    ///
+    /// Default: `[]`
+    pub fn commands(&self) -> Vec<String> {
+        self.commands.clone().unwrap_or_default()
+    }
+
+    /// Checks whether the rule applies to a project. A project matches if any of the rule's
+    /// files exist in it, or its name matches any of the rule's globs. Rules without files or
+    /// names match every project
+    ///
+    /// # Parameters
+    ///
+    /// - `project_name` The name of the cloned project
+    /// - `project_root` The root of the cloned project
+    pub fn matches(&self, project_name: &str, project_root: &Path) -> bool {
+        let (files, names) = (self.files(), self.names());
+
+        if files.is_empty() && names.is_empty() {
+            return true;
+        }
+
+        files.iter().any(|file| project_root.join(file).exists())
+            || names.iter().any(|name| glob::matches(name, project_name))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use crate::{config::WorkflowsConfig, test_utils::TempDir};
+
+    #[test]
+    fn on_clone_works() {
+        let toml = "\
+                    [[on_clone]]\n\
+                    files = [\"Cargo.toml\"]\n\
+                    commands = [\"cargo fetch\"]\n\
+                    [[on_clone]]\n\
+                    names = [\"*-api\"]\n\
+                    commands = [\"direnv allow\"]";
+
+        let config: WorkflowsConfig = toml::from_str(toml).unwrap();
+
+        let rules = config.on_clone();
+        assert_eq!(rules.len(), 2);
+        assert_eq!(rules[0].files(), vec!["Cargo.toml"]);
+        assert_eq!(rules[0].names(), Vec::<String>::new());
+        assert_eq!(rules[1].names(), vec!["*-api"]);
+        assert_eq!(rules[1].commands(), vec!["direnv allow"]);
+    }
+
+    #[test]
+    fn default_on_clone_works() {
+        let config: WorkflowsConfig = toml::from_str("").unwrap();
+
+        assert!(config.on_clone().is_empty());
+    }
+
+    #[test]
+    fn matches_works() {
+        let project_root = TempDir::new("on-clone");
+        fs::write(project_root.join("package.json"), "{}").unwrap();
+
+        let toml = "\
+                    [[on_clone]]\n\
+                    files = [\"Cargo.toml\", \"package.json\"]\n\
+                    [[on_clone]]\n\
+                    files = [\"go.mod\"]\n\
+                    names = [\"*-api\"]\n\
+                    [[on_clone]]\n\
+                    commands = [\"direnv allow\"]";
+
+        let config: WorkflowsConfig = toml::from_str(toml).unwrap();
+        let rules = config.on_clone();
+
+        assert!(rules[0].matches("website", &project_root));
+        assert!(!rules[1].matches("website", &project_root));
+        assert!(rules[1].matches("billing-api", &project_root));
+        assert!(rules[2].matches("website", &project_root));
+    }
+}
@@ -1,10 +1,83 @@
+use std::{
+    collections::HashSet,
+    fmt, fs,
+    path::{Path, PathBuf},
+};
+
 use serde::Deserialize;
 
+use crate::variables::validate_prompt_name;
+
+const DEFAULT_CONTINUE_ON_ERROR: bool = false;
+
+/// Gets the directory templates are stored in, ~/.config/workflows/templates/
+pub fn templates_dir() -> Option<PathBuf> {
+    Some(dirs::config_dir()?.join("workflows/").join("templates/"))
+}
+
+/// Reads every `*.toml` file in the given directory as a template, in order of file name. If a
+/// file doesn't set a name, the name of the file is used
+///
+/// # Parameters
+///
+/// - `dir` The directory to read templates from
+///
+/// # Returns
+///
+/// The templates read, or a message describing why a file couldn't be read
+pub fn read_template_files(dir: &Path) -> Vec<Result<WorkspaceTemplate, String>> {
+    let mut paths: Vec<PathBuf> = match fs::read_dir(dir) {
+        Ok(entries) => entries
+            .filter_map(|entry| Some(entry.ok()?.path()))
+            .filter(|path| path.is_file() && path.extension().is_some_and(|ext| ext == "toml"))
+            .collect(),
+        Err(_) => return vec![],
+    };
+    paths.sort();
+
+    paths
+        .iter()
+        .map(|path| {
+            let toml_string = fs::read_to_string(path)
+                .map_err(|err| format!("Couldn't read template {}: {}", path.display(), err))?;
+
+            let mut template: WorkspaceTemplate = toml::from_str(&toml_string).map_err(|err| {
+                format!(
+                    "Couldn't parse template from {}\n{}",
+                    path.display(),
+                    err.to_string().trim_end()
+                )
+            })?;
+
+            if template.name.is_empty() {
+                template.name = path
+                    .file_stem()
+                    .map(|stem| stem.to_string_lossy().to_string())
+                    .unwrap_or_default();
+            }
+
+            Ok(template)
+        })
+        .collect()
+}
+
 #[derive(Debug, Deserialize, Default, Clone, PartialEq, Eq)]
 #[serde(default)]
 pub struct WorkspaceTemplate {
     name: String,
-    commands: Vec<String>,
+    commands: Vec<TemplateStep>,
+    prompt: Vec<TemplatePrompt>,
+    skeleton: Option<String>,
+    extends: Vec<String>,
+    publish: Option<bool>,
+    visibility: Option<String>,
+    description: Option<String>,
+
+    /// The skeletons of the templates this template extends, filled in by [`resolve`]
+    ///
+    /// [`resolve`]: WorkspaceTemplate::resolve
+    #[serde(skip)]
+    inherited_skeletons: Vec<PathBuf>,
 }
 
 impl WorkspaceTemplate {
@@ -12,14 +85,293 @@ impl WorkspaceTemplate {
         self.name.as_ref()
     }
 
-    pub fn commands(&self) -> &[String] {
+    pub fn commands(&self) -> &[TemplateStep] {
         self.commands.as_ref()
     }
+
+    /// The questions to ask the user before the template's commands are run
+    pub fn prompts(&self) -> &[TemplatePrompt] {
+        self.prompt.as_ref()
+    }
+
+    /// The names of the templates whose prompts, skeletons and commands come before this
+    /// template's own
+    pub fn extends(&self) -> &[String] {
+        self.extends.as_ref()
+    }
+
+    /// Whether projects created with this template should be published to GitHub, overriding
+    /// the `publish` option in the github config
+    pub fn publish(&self) -> Option<bool> {
+        self.publish
+    }
+
+    /// The visibility of the GitHub repo created when publishing, overriding the
+    /// `repo_visibility` option in the github config
+    pub fn visibility(&self) -> Option<String> {
+        self.visibility.clone()
+    }
+
+    /// The description of the GitHub repo created when publishing. Can contain `{{variable}}`
+    /// placeholders
+    pub fn description(&self) -> Option<String> {
+        self.description.clone()
+    }
+
+    /// The directories whose contents are copied into the new project before the template's
+    /// commands are run, starting with those of the templates it extends
+    pub fn skeletons(&self) -> Vec<PathBuf> {
+        self.inherited_skeletons
+            .iter()
+            .cloned()
+            .chain(self.skeleton())
+            .collect()
+    }
+
+    /// The directory of this template's own skeleton. Relative paths are relative to
+    /// ~/.config/workflows/templates/
+    fn skeleton(&self) -> Option<PathBuf> {
+        let skeleton = self.skeleton.clone()?;
+
+        if let Some(path) = skeleton.strip_prefix("~/") {
+            return Some(dirs::home_dir()?.join(path));
+        }
+
+        let skeleton = PathBuf::from(skeleton);
+        match skeleton.is_absolute() {
+            true => Some(skeleton),
+            false => Some(templates_dir()?.join(skeleton)),
+        }
+    }
+
+    /// Merges the template with the templates it extends, so the steps of the parent templates
+    /// are run first. A template extended through more than one parent is only merged once
+    ///
+    /// # Parameters
+    ///
+    /// - `templates` Every template available, used to look up parent templates
+    ///
+    /// # Returns
+    ///
+    /// The merged template, or an error if a parent template doesn't exist or templates extend
+    /// each other in a cycle
+    pub fn resolve(&self, templates: &[WorkspaceTemplate]) -> Result<Self, ResolveError> {
+        let mut resolved = Self {
+            name: self.name.clone(),
+            extends: self.extends.clone(),
+            ..Default::default()
+        };
+
+        self.merge_with_parents(templates, &mut vec![], &mut HashSet::new(), &mut resolved)?;
+
+        Ok(resolved)
+    }
+
+    /// Recursively merges a template's parents and then the template itself into the resolved
+    /// template. The templates currently being merged are tracked to detect cycles, and
+    /// templates that have already been merged through another parent are skipped
+    fn merge_with_parents(
+        &self,
+        templates: &[WorkspaceTemplate],
+        chain: &mut Vec<String>,
+        merged: &mut HashSet<String>,
+        resolved: &mut Self,
+    ) -> Result<(), ResolveError> {
+        if chain.contains(&self.name) {
+            let mut cycle = chain.clone();
+            cycle.push(self.name.clone());
+            return Err(ResolveError::Cycle(cycle));
+        }
+        if merged.contains(&self.name) {
+            return Ok(());
+        }
+        for prompt in &self.prompt {
+            validate_prompt_name(prompt.name()).map_err(|reason| ResolveError::InvalidPrompt {
+                template: self.name.clone(),
+                reason,
+            })?;
+        }
+        chain.push(self.name.clone());
+
+        for parent_name in &self.extends {
+            templates
+                .iter()
+                .find(|template| template.name() == parent_name)
+                .ok_or(ResolveError::Missing(parent_name.to_owned()))?
+                .merge_with_parents(templates, chain, merged, resolved)?;
+        }
+
+        resolved.merge(self.clone());
+        chain.pop();
+        merged.insert(self.name.clone());
+
+        Ok(())
+    }
+
+    /// Adds another template's prompts, skeletons and commands after this template's. Prompts
+    /// with the same name are replaced rather than asked twice, as are any publish options the
+    /// other template sets
+    fn merge(&mut self, other: WorkspaceTemplate) {
+        let skeletons = other.skeletons();
+
+        self.publish = other.publish.or(self.publish);
+        self.visibility = other.visibility.or(self.visibility.take());
+        self.description = other.description.or(self.description.take());
+
+        for prompt in other.prompt {
+            self.prompt.retain(|existing| existing.name != prompt.name);
+            self.prompt.push(prompt);
+        }
+
+        self.inherited_skeletons.extend(skeletons);
+        self.commands.extend(other.commands);
+    }
+}
+
+/// The ways resolving a template's `extends` can fail
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResolveError {
+    /// A template extends a template that doesn't exist
+    Missing(String),
+    /// Templates extend each other in a cycle, listed in the order they extend each other
+    Cycle(Vec<String>),
+    /// A template has a prompt that can't be used as a variable
+    InvalidPrompt { template: String, reason: String },
+}
+
+impl fmt::Display for ResolveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ResolveError::Missing(name) => write!(f, "no template named {} exists", name),
+            ResolveError::Cycle(chain) => {
+                write!(f, "templates extend in a cycle: {}", chain.join(" -> "))
+            }
+            ResolveError::InvalidPrompt { template, reason } => {
+                write!(f, "{} in the {} template", reason, template)
+            }
+        }
+    }
+}
+
+/// A question asked when a template is used, with the answer exposed to the template's commands
+/// as `WORKFLOWS_<NAME>`
+///
+/// ```toml
+/// [[template.prompt]]
+/// name = "license"
+/// message = "Which license?"
+/// options = ["MIT", "Apache-2.0"]
+/// ```
+#[derive(Debug, Deserialize, Default, Clone, PartialEq, Eq)]
+#[serde(default)]
+pub struct TemplatePrompt {
+    name: String,
+    message: Option<String>,
+    options: Vec<String>,
+    default: Option<String>,
+}
+
+impl TemplatePrompt {
+    /// The name of the variable the answer is stored in
+    pub fn name(&self) -> &str {
+        self.name.as_ref()
+    }
+
+    /// The question shown to the user
+    ///
+    /// Default: the prompt's name
+    pub fn message(&self) -> String {
+        self.message.clone().unwrap_or(format!("{}: ", self.name))
+    }
+
+    /// The answers the user can pick from. If empty, the user can enter any answer
+    pub fn options(&self) -> &[String] {
+        self.options.as_ref()
+    }
+
+    /// The answer used when the user doesn't enter one
+    ///
+    /// Default: `""`
+    pub fn default_answer(&self) -> String {
+        self.default.clone().unwrap_or_default()
+    }
+}
+
+/// A single command run by a template
+///
+/// Steps can either be written as a plain string, or as a table when more control is needed:
+///
+/// ```toml
+/// commands = ["cargo init", { command = "cargo fmt", continue_on_error = true }]
+/// ```
+#[derive(Debug, Deserialize, Clone, PartialEq, Eq)]
+#[serde(from = "RawTemplateStep")]
+pub struct TemplateStep {
+    command: String,
+    continue_on_error: Option<bool>,
+}
+
+impl TemplateStep {
+    /// The shell command to run
+    pub fn command(&self) -> &str {
+        self.command.as_ref()
+    }
+
+    /// Whether the template should keep running if this step fails
+    ///
+    /// Default: `false`
+    pub fn continue_on_error(&self) -> bool {
+        self.continue_on_error.unwrap_or(DEFAULT_CONTINUE_ON_ERROR)
+    }
+}
+
+/// The forms a [`TemplateStep`] can be written in
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawTemplateStep {
+    Command(String),
+    Detailed {
+        command: String,
+        continue_on_error: Option<bool>,
+    },
+}
+
+impl From<RawTemplateStep> for TemplateStep {
+    fn from(value: RawTemplateStep) -> Self {
+        match value {
+            RawTemplateStep::Command(command) => Self {
+                command,
+                continue_on_error: None,
+            },
+            RawTemplateStep::Detailed {
+                command,
+                continue_on_error,
+            } => Self {
+                command,
+                continue_on_error,
+            },
+        }
+    }
+}
+
+impl From<&str> for TemplateStep {
+    fn from(value: &str) -> Self {
+        RawTemplateStep::Command(value.to_string()).into()
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::config::{templates::WorkspaceTemplate, WorkflowsConfig};
+    use std::{fs, path::PathBuf};
+
+    use crate::config::{
+        templates::{
+            read_template_files, templates_dir, ResolveError, TemplatePrompt, TemplateStep,
+            WorkspaceTemplate, DEFAULT_CONTINUE_ON_ERROR,
+        },
+        WorkflowsConfig,
+    };
+    use crate::test_utils::TempDir;
 
     #[test]
     fn templates_works() {
@@ -37,11 +389,13 @@ mod tests {
         let wanted = vec![
             WorkspaceTemplate {
                 name: "rust".to_string(),
-                commands: vec!["cargo init".to_string()],
+                commands: vec!["cargo init".into()],
+                ..Default::default()
             },
             WorkspaceTemplate {
                 name: "go".to_string(),
-                commands: vec!["go mod init".to_string()],
+                commands: vec!["go mod init".into()],
+                ..Default::default()
             },
         ];
 
@@ -56,4 +410,394 @@ mod tests {
 
         assert_eq!(config.templates(), vec![]);
     }
+
+    #[test]
+    fn continue_on_error_works() {
+        let toml = "\
+[[template]]
+name = 'node'
+commands = ['npm init -y', { command = 'npm audit', continue_on_error = true }]";
+
+        let config: WorkflowsConfig = toml::from_str(toml).unwrap();
+
+        let steps = config.templates()[0].commands().to_vec();
+
+        assert_eq!(
+            steps,
+            vec![
+                TemplateStep::from("npm init -y"),
+                TemplateStep {
+                    command: "npm audit".to_string(),
+                    continue_on_error: Some(true),
+                }
+            ]
+        );
+        assert!(steps[1].continue_on_error());
+    }
+
+    #[test]
+    fn default_continue_on_error_works() {
+        let toml = "\
+[[template]]
+name = 'node'
+commands = [{ command = 'npm init -y' }]";
+
+        let config: WorkflowsConfig = toml::from_str(toml).unwrap();
+
+        let step = config.templates()[0].commands()[0].clone();
+
+        assert_eq!(step.continue_on_error, None);
+        assert_eq!(step.continue_on_error(), DEFAULT_CONTINUE_ON_ERROR);
+    }
+
+    #[test]
+    fn prompts_work() {
+        let toml = "\
+[[template]]
+name = 'rust'
+commands = ['cargo init']
+
+[[template.prompt]]
+name = 'license'
+message = 'License: '
+options = ['MIT', 'GPL-3.0']
+
+[[template.prompt]]
+name = 'description'";
+
+        let config: WorkflowsConfig = toml::from_str(toml).unwrap();
+
+        let prompts = config.templates()[0].prompts().to_vec();
+
+        assert_eq!(
+            prompts,
+            vec![
+                TemplatePrompt {
+                    name: "license".to_string(),
+                    message: Some("License: ".to_string()),
+                    options: vec!["MIT".to_string(), "GPL-3.0".to_string()],
+                    default: None,
+                },
+                TemplatePrompt {
+                    name: "description".to_string(),
+                    ..Default::default()
+                }
+            ]
+        );
+    }
+
+    #[test]
+    fn default_prompt_values_work() {
+        let toml = "\
+[[template]]
+name = 'rust'
+
+[[template.prompt]]
+name = 'description'";
+
+        let config: WorkflowsConfig = toml::from_str(toml).unwrap();
+
+        let prompt = config.templates()[0].prompts()[0].clone();
+
+        assert_eq!(prompt.message(), "description: ");
+        assert_eq!(prompt.default_answer(), "");
+    }
+
+    #[test]
+    fn skeleton_works() {
+        let toml = "\
+[[template]]
+name = 'rust-cli'
+skeleton = 'rust-cli/'
+
+[[template]]
+name = 'go'
+skeleton = '~/skeletons/go/'
+
+[[template]]
+name = 'node'
+skeleton = '/opt/skeletons/node/'";
+
+        let config: WorkflowsConfig = toml::from_str(toml).unwrap();
+
+        let templates = config.templates();
+
+        assert_eq!(
+            templates[0].skeleton(),
+            Some(templates_dir().unwrap().join("rust-cli/"))
+        );
+        assert_eq!(
+            templates[1].skeleton(),
+            Some(dirs::home_dir().unwrap().join("skeletons/go/"))
+        );
+        assert_eq!(templates[2].skeleton(), Some("/opt/skeletons/node/".into()));
+    }
+
+    #[test]
+    fn default_skeleton_works() {
+        let toml = "\
+[[template]]
+name = 'rust'";
+
+        let config: WorkflowsConfig = toml::from_str(toml).unwrap();
+
+        assert_eq!(config.templates()[0].skeleton, None);
+        assert!(config.templates()[0].skeletons().is_empty());
+    }
+
+    #[test]
+    fn extends_works() {
+        let toml = "\
+[[template]]
+name = 'base'
+commands = ['git init']
+skeleton = '/skeletons/base/'
+
+[[template]]
+name = 'ci'
+commands = ['pre-commit install']
+
+[[template]]
+name = 'rust'
+extends = ['base', 'ci']
+commands = ['cargo init']";
+
+        let config: WorkflowsConfig = toml::from_str(toml).unwrap();
+
+        let templates = config.templates();
+        let resolved = templates[2].resolve(&templates).unwrap();
+
+        assert_eq!(resolved.name(), "rust");
+        assert_eq!(
+            resolved.commands(),
+            vec![
+                TemplateStep::from("git init"),
+                TemplateStep::from("pre-commit install"),
+                TemplateStep::from("cargo init"),
+            ]
+        );
+        assert_eq!(
+            resolved.skeletons(),
+            vec![PathBuf::from("/skeletons/base/")]
+        );
+    }
+
+    #[test]
+    fn extends_merges_shared_parents_once() {
+        let toml = "\
+[[template]]
+name = 'base'
+commands = ['git init']
+skeleton = '/skeletons/base/'
+
+[[template]]
+name = 'ci'
+extends = ['base']
+commands = ['pre-commit install']
+
+[[template]]
+name = 'rust'
+extends = ['base', 'ci']
+commands = ['cargo init']";
+
+        let config: WorkflowsConfig = toml::from_str(toml).unwrap();
+
+        let templates = config.templates();
+        let resolved = templates[2].resolve(&templates).unwrap();
+
+        assert_eq!(
+            resolved.commands(),
+            vec![
+                TemplateStep::from("git init"),
+                TemplateStep::from("pre-commit install"),
+                TemplateStep::from("cargo init"),
+            ]
+        );
+        assert_eq!(
+            resolved.skeletons(),
+            vec![PathBuf::from("/skeletons/base/")]
+        );
+    }
+
+    #[test]
+    fn extends_replaces_parent_prompts() {
+        let toml = "\
+[[template]]
+name = 'base'
+
+[[template.prompt]]
+name = 'license'
+default = 'MIT'
+
+[[template]]
+name = 'rust'
+extends = ['base']
+
+[[template.prompt]]
+name = 'license'
+default = 'GPL-3.0'";
+
+        let config: WorkflowsConfig = toml::from_str(toml).unwrap();
+
+        let templates = config.templates();
+        let resolved = templates[1].resolve(&templates).unwrap();
+
+        assert_eq!(resolved.prompts().len(), 1);
+        assert_eq!(resolved.prompts()[0].default_answer(), "GPL-3.0");
+    }
+
+    #[test]
+    fn extends_detects_cycles() {
+        let toml = "\
+[[template]]
+name = 'a'
+extends = ['b']
+
+[[template]]
+name = 'b'
+extends = ['a']";
+
+        let config: WorkflowsConfig = toml::from_str(toml).unwrap();
+
+        let templates = config.templates();
+
+        assert_eq!(
+            templates[0].resolve(&templates),
+            Err(ResolveError::Cycle(vec![
+                "a".to_string(),
+                "b".to_string(),
+                "a".to_string()
+            ]))
+        );
+    }
+
+    #[test]
+    fn resolve_rejects_invalid_prompts() {
+        let toml = "\
+[[template]]
+name = 'base'
+
+[[template.prompt]]
+name = 'project_name'
+
+[[template]]
+name = 'rust'
+extends = ['base']
+
+[[template]]
+name = 'blank'
+
+[[template.prompt]]
+message = 'No name?'";
+
+        let config: WorkflowsConfig = toml::from_str(toml).unwrap();
+
+        let templates = config.templates();
+
+        assert!(matches!(
+            templates[1].resolve(&templates),
+            Err(ResolveError::InvalidPrompt { template, .. }) if template == "base"
+        ));
+        assert!(matches!(
+            templates[2].resolve(&templates),
+            Err(ResolveError::InvalidPrompt { template, .. }) if template == "blank"
+        ));
+    }
+
+    #[test]
+    fn extends_missing_template_errors() {
+        let toml = "\
+[[template]]
+name = 'rust'
+extends = ['base']";
+
+        let config: WorkflowsConfig = toml::from_str(toml).unwrap();
+
+        let templates = config.templates();
+
+        assert_eq!(
+            templates[0].resolve(&templates),
+            Err(ResolveError::Missing("base".to_string()))
+        );
+    }
+
+    #[test]
+    fn read_template_files_works() {
+        let dir = TempDir::new("templates");
+        fs::create_dir_all(dir.join("rust-cli")).unwrap();
+        fs::write(dir.join("rust.toml"), "commands = ['cargo init']").unwrap();
+        fs::write(
+            dir.join("go.toml"),
+            "name = 'Go'\ncommands = ['go mod init']",
+        )
+        .unwrap();
+        fs::write(dir.join("broken.toml"), "commands = ").unwrap();
+        fs::write(dir.join("notes.md"), "not a template").unwrap();
+
+        let mut templates = read_template_files(&dir);
+
+        let broken = templates.remove(0).unwrap_err();
+        assert!(broken.starts_with(&format!(
+            "Couldn't parse template from {}\nTOML parse error at line 1, column 12",
+            dir.join("broken.toml").display()
+        )));
+
+        assert_eq!(
+            templates,
+            vec![
+                Ok(WorkspaceTemplate {
+                    name: "Go".to_string(),
+                    commands: vec!["go mod init".into()],
+                    ..Default::default()
+                }),
+                Ok(WorkspaceTemplate {
+                    name: "rust".to_string(),
+                    commands: vec!["cargo init".into()],
+                    ..Default::default()
+                }),
+            ]
+        );
+    }
+
+    #[test]
+    fn publish_options_work() {
+        let toml = "\
+[[template]]
+name = 'base'
+publish = true
+visibility = 'public'
+
+[[template]]
+name = 'rust'
+extends = ['base']
+visibility = 'private'
+description = 'The {{project_name}} crate'";
+
+        let config: WorkflowsConfig = toml::from_str(toml).unwrap();
+
+        let templates = config.templates();
+        let resolved = templates[1].resolve(&templates).unwrap();
+
+        assert_eq!(resolved.publish(), Some(true));
+        assert_eq!(resolved.visibility(), Some("private".to_string()));
+        assert_eq!(
+            resolved.description(),
+            Some("The {{project_name}} crate".to_string())
+        );
+    }
+
+    #[test]
+    fn default_publish_options_work() {
+        let toml = "\
+[[template]]
+name = 'rust'";
+
+        let config: WorkflowsConfig = toml::from_str(toml).unwrap();
+
+        let template = config.templates()[0].clone();
+
+        assert_eq!(template.publish(), None);
+        assert_eq!(template.visibility(), None);
+        assert_eq!(template.description(), None);
+    }
 }
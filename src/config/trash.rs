@@ -0,0 +1,65 @@
+//! This module contains the logic for trash configuration
+
+use serde::Deserialize;
+
+const DEFAULT_ENABLED: bool = true;
+const DEFAULT_RETENTION_DAYS: u64 = 30;
+
+#[derive(Debug, Deserialize, Default, Clone, PartialEq, Eq)]
+pub struct TrashConfig {
+    /// Whether deleted projects should be moved to the trash
+    enabled: Option<bool>,
+
+    /// How many days projects are kept in the trash before being deleted for good
+    retention_days: Option<u64>,
+}
+
+impl TrashConfig {
+    /// Whether deleted projects should be moved to the trash, instead of being deleted for good
+    ///
+    /// Default: `true`
+    pub fn enabled(&self) -> bool {
+        self.enabled.unwrap_or(DEFAULT_ENABLED)
+    }
+
+    /// How many days projects are kept in the trash before being deleted for good
+    ///
+    /// Default: `30`
+    pub fn retention_days(&self) -> u64 {
+        self.retention_days.unwrap_or(DEFAULT_RETENTION_DAYS)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::{
+        trash::{DEFAULT_ENABLED, DEFAULT_RETENTION_DAYS},
+        WorkflowsConfig,
+    };
+
+    #[test]
+    fn trash_works() {
+        let toml = "\
+                    [trash]\n\
+                    enabled = false\n\
+                    retention_days = 7";
+
+        let config: WorkflowsConfig = toml::from_str(toml).unwrap();
+
+        assert!(!config.trash().enabled());
+        assert_eq!(config.trash().retention_days(), 7);
+    }
+
+    #[test]
+    fn default_trash_works() {
+        let toml = "[trash]";
+
+        let config: WorkflowsConfig = toml::from_str(toml).unwrap();
+
+        assert_eq!(config.trash.clone().unwrap().enabled, None);
+        assert_eq!(config.trash.clone().unwrap().retention_days, None);
+
+        assert_eq!(config.trash().enabled(), DEFAULT_ENABLED);
+        assert_eq!(config.trash().retention_days(), DEFAULT_RETENTION_DAYS);
+    }
+}
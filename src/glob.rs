@@ -0,0 +1,72 @@
+//! This module contains a minimal glob matcher, used to match project and file names in the
+//! config
+//!
+//! Only `*` (any run of characters) and `?` (any single character) are supported
+
+/// Checks whether the text matches the glob pattern
+///
+/// # Parameters
+///
+/// - `pattern` The glob pattern, e.g. `*-api`
+/// - `text`    The text to match against
+pub fn matches(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+
+    let (mut p, mut t) = (0, 0);
+    // The position of the last `*` in the pattern, and the text position it was tried at
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, t));
+                p += 1;
+            }
+            Some(c) if *c == '?' || *c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match backtrack {
+                // Letting the last `*` consume one more character and trying again
+                Some((star, star_t)) => {
+                    backtrack = Some((star, star_t + 1));
+                    p = star + 1;
+                    t = star_t + 1;
+                }
+                None => return false,
+            },
+        }
+    }
+
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::matches;
+
+    #[test]
+    fn literal_patterns_work() {
+        assert!(matches("workflows", "workflows"));
+        assert!(!matches("workflows", "workflow"));
+        assert!(!matches("workflow", "workflows"));
+    }
+
+    #[test]
+    fn star_works() {
+        assert!(matches("*", ""));
+        assert!(matches("*-api", "billing-api"));
+        assert!(matches("client-*", "client-"));
+        assert!(matches("*.env*", "config/.env.local"));
+        assert!(matches("a*b*c", "aXbYbZc"));
+        assert!(!matches("*-api", "billing-apis"));
+    }
+
+    #[test]
+    fn question_mark_works() {
+        assert!(matches("v?", "v2"));
+        assert!(!matches("v?", "v"));
+        assert!(!matches("v?", "v10"));
+    }
+}
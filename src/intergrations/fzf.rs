@@ -2,7 +2,9 @@
 //!
 //! Heavily based on the [rust_fzf library](https://crates.io/crates/rust_fzf)
 
-use fzf_wrapped::Fzf;
+use std::time::SystemTime;
+
+use fzf_wrapped::{Fzf, FzfBuilder};
 
 use crate::commands;
 use crate::config::fzf::FzfConfig;
@@ -10,6 +12,41 @@ use crate::config::templates::WorkspaceTemplate;
 use crate::config::WorkflowsConfig;
 use crate::intergrations;
 use crate::repo::Repo;
+use crate::state::{
+    archive,
+    history::{self, History},
+    tags::{load_tags, Tags},
+};
+
+/// Which local projects are shown when selecting a project
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum ProjectFilter {
+    /// Every project
+    #[default]
+    All,
+    /// Only projects with the given tag, e.g. `workflows #client`
+    Tag(String),
+    /// Only the user's favourite projects
+    Favourites,
+    /// Every local project, leaving out archived projects, for commands that need a checkout
+    Checkouts,
+}
+
+impl ProjectFilter {
+    /// Whether a project is shown
+    ///
+    /// # Parameters
+    ///
+    /// - `project` The project to check
+    /// - `tags`    The user's tags
+    fn shows(&self, project: &Repo, tags: &Tags) -> bool {
+        match self {
+            ProjectFilter::All | ProjectFilter::Checkouts => true,
+            ProjectFilter::Tag(tag) => tags.has_tag(project, tag),
+            ProjectFilter::Favourites => tags.is_favourite(project),
+        }
+    }
+}
 
 /// Run fzf to select a project. Has flags to enable what projects will be shown
 ///
@@ -18,7 +55,9 @@ use crate::repo::Repo;
 /// - `prompt`      The prompt to display in the fzf menu
 /// - `show_local`  Whether to show local projects or not
 /// - `show_remote` Whether to display github projects or not. Note: github integration also has to
-///                 be enabled
+///   be enabled
+/// - `filter`      Which local projects to show. Remote and archived projects are only shown when
+///   every local project is
 /// - `config`      The users config
 ///
 /// # Returns
@@ -29,18 +68,43 @@ pub fn run_fzf(
     prompt: &str,
     show_local: bool,
     show_remote: bool,
+    filter: &ProjectFilter,
     config: &WorkflowsConfig,
 ) -> Option<Repo> {
+    // Loaded before fzf starts, so any warning isn't drawn over
+    let tags = commands::state_or_default(load_tags());
+    let history = match history::history_file() {
+        Some(history_file) if config.history().enabled() => {
+            commands::state_or_default(History::load(&history_file))
+        }
+        _ => History::default(),
+    };
+
     let mut fzf = get_fzf_instance(prompt, config.fzf());
 
     fzf.run().expect("Failed to run fzf");
 
-    let local_projects = commands::get_local_projects(config.general().projects_dirs());
+    let mut local_projects = commands::get_local_projects(config.general().projects_dirs());
+    local_projects.retain(|project| filter.shows(project, &tags));
+    rank_projects(&mut local_projects, &history, &tags, config);
+
+    let show_remote = show_remote && *filter == ProjectFilter::All;
+
     if show_local {
         fzf.add_items(local_projects.clone())
             .expect("Failed to add local repos");
     }
 
+    let mut archived_projects = vec![];
+    if show_local && config.archive().show_in_picker() && *filter == ProjectFilter::All {
+        archived_projects = get_archived_projects(&local_projects, config);
+        let _ = fzf.add_items(
+            archived_projects
+                .iter()
+                .map(|x| format!("{}{}", config.archive().indicator(), x.name())),
+        );
+    }
+
     let mut git_projects = vec![];
     if config.github().enabled() && show_remote {
         git_projects = intergrations::gh::get_gh_repos(&local_projects);
@@ -70,10 +134,18 @@ pub fn run_fzf(
         .map(|x| x.to_owned())
         .next();
 
-    let project = match filtered_project {
-        Some(local_project) => local_project,
-        None => {
-            // If the project is not found with the previous search, it's a remote project
+    // fzf trims its output, so the indicator may have lost its whitespace
+    let archive_indicator = config.archive().indicator();
+    let archived_project = project_name
+        .strip_prefix(archive_indicator.trim())
+        .filter(|_| !archive_indicator.trim().is_empty())
+        .and_then(|name| archived_projects.iter().find(|x| x.name() == name.trim()));
+
+    let project = match (filtered_project, archived_project) {
+        (Some(local_project), _) => local_project,
+        (None, Some(archived_project)) => archived_project.to_owned(),
+        (None, None) => {
+            // If the project is not found with the previous searches, it's a remote project
             let trimmed_name = &project_name[config.github().project_indicator().len()..];
             projects
                 .iter()
@@ -86,7 +158,94 @@ pub fn run_fzf(
     Some(project)
 }
 
-/// Prompts the user to select a template
+/// Runs fzf with local projects, letting the user select any number of them with tab
+///
+/// # Parameters
+///
+/// - `prompt` The prompt to display in the fzf menu
+/// - `config` The users config
+///
+/// # Returns
+///
+/// The selected projects, in the order they were selected
+pub fn run_fzf_multi(prompt: &str, config: &WorkflowsConfig) -> Vec<Repo> {
+    let mut fzf = get_fzf_builder(prompt, config.fzf())
+        .custom_args(["--multi"])
+        .build()
+        .unwrap();
+
+    fzf.run().expect("Failed to run fzf");
+
+    let local_projects = commands::get_local_projects(config.general().projects_dirs());
+    fzf.add_items(local_projects.clone())
+        .expect("Failed to add local repos");
+
+    let output = fzf.output().unwrap_or_default();
+
+    output
+        .lines()
+        .filter_map(|name| {
+            local_projects
+                .iter()
+                .find(|project| project.name() == name.trim())
+                .cloned()
+        })
+        .collect()
+}
+
+/// Orders projects by the user's pinned projects and favourites, then by frecency if the history
+/// is enabled
+///
+/// # Parameters
+///
+/// - `projects` The projects to order
+/// - `history`  The history of opened projects
+/// - `tags`     The user's tags, for their favourites
+/// - `config`   The user's config
+fn rank_projects(projects: &mut [Repo], history: &History, tags: &Tags, config: &WorkflowsConfig) {
+    history.rank(
+        projects,
+        &config.history().pinned(),
+        tags.favourites(),
+        SystemTime::now(),
+    );
+}
+
+/// Gets the projects in the user's archive, skipping any that are checked out again. Only the
+/// most recent archive of each project is included
+///
+/// # Parameters
+///
+/// - `local_projects` The user's local projects
+/// - `config`         The user's config
+fn get_archived_projects(local_projects: &[Repo], config: &WorkflowsConfig) -> Vec<Repo> {
+    let archive_dir = match config.archive().dir() {
+        Some(archive_dir) => archive_dir,
+        None => return vec![],
+    };
+
+    let mut archived: Vec<Repo> = vec![];
+    for project in archive::list(&archive_dir) {
+        let repo = Repo::new_archived(project.name(), project.project_dir());
+
+        if !local_projects.contains(&repo) && !archived.contains(&repo) {
+            archived.push(repo);
+        }
+    }
+
+    archived
+}
+
+/// A template selected by the user
+pub enum SelectedTemplate {
+    /// A template defined in the user's config
+    Workflows(WorkspaceTemplate),
+    /// A GitHub template repo, in the format `owner/repo`
+    Github(String),
+}
+
+/// Prompts the user to select a template. GitHub template repos from the configured owners are
+/// listed after the user's templates
 ///
 /// # Parameters
 ///
@@ -95,28 +254,46 @@ pub fn run_fzf(
 /// # Returns
 ///
 /// `None` if the user doesn't have any templates or selects blank
-pub fn get_template(config: WorkflowsConfig) -> Option<WorkspaceTemplate> {
+pub fn get_template(config: WorkflowsConfig) -> Option<SelectedTemplate> {
     let templates = config.templates();
     let fzf_config = config.fzf();
+    let indicator = config.github().project_indicator();
 
-    if templates.is_empty() {
+    let template_repos = match config.github().enabled() {
+        true => intergrations::gh::get_template_repos(&config.github().template_owners()),
+        false => vec![],
+    };
+
+    if templates.is_empty() && template_repos.is_empty() {
         return None;
     }
 
     let mut template_names: Vec<String> = templates.iter().map(|x| x.name().to_string()).collect();
+    template_names.extend(
+        template_repos
+            .iter()
+            .map(|repo| format!("{}{}", indicator, repo)),
+    );
     template_names.push(fzf_config.no_template_option());
 
     let fzf = get_fzf_instance(fzf_config.template_prompt(), config.fzf());
 
     let selected_template = fzf_wrapped::run_with_output(fzf, template_names)?;
 
-    match selected_template == fzf_config.no_template_option() {
-        true => None,
-        false => templates
-            .iter()
-            .find(|x| x.name() == selected_template)
-            .cloned(),
+    if selected_template == fzf_config.no_template_option() {
+        return None;
     }
+
+    if let Some(template) = templates.iter().find(|x| x.name() == selected_template) {
+        return Some(SelectedTemplate::Workflows(template.to_owned()));
+    }
+
+    // fzf trims its output, so the indicator may have lost its whitespace
+    let trimmed_name = selected_template.trim_start_matches(indicator.trim());
+    template_repos
+        .into_iter()
+        .find(|repo| repo == trimmed_name.trim())
+        .map(SelectedTemplate::Github)
 }
 
 /// Gets the users selected project directory. If there is only one option, then fzf is skipped
@@ -140,6 +317,61 @@ pub fn get_project_dir(config: &WorkflowsConfig) -> Option<String> {
     fzf_wrapped::run_with_output(fzf, projects_dirs)
 }
 
+/// Prompts the user to pick one of the given options
+///
+/// # Parameters
+///
+/// - `prompt`  The prompt for fzf to have
+/// - `options` The options the user can pick from
+/// - `config`  The user's config
+///
+/// # Returns
+///
+/// `None` if the user doesn't select an option
+pub fn select_option(
+    prompt: impl Into<String>,
+    options: &[String],
+    config: &WorkflowsConfig,
+) -> Option<String> {
+    let fzf = get_fzf_instance(prompt, config.fzf());
+
+    let selected = fzf_wrapped::run_with_output(fzf, options.to_vec())?;
+
+    match selected.is_empty() {
+        true => None,
+        false => Some(selected),
+    }
+}
+
+/// Prompts the user to pick any number of the given options with tab
+///
+/// # Parameters
+///
+/// - `prompt`  The prompt for fzf to have
+/// - `options` The options the user can pick from
+/// - `config`  The user's config
+///
+/// # Returns
+///
+/// The selected options, which is empty if the user doesn't select any
+pub fn select_options(
+    prompt: impl Into<String>,
+    options: &[String],
+    config: &WorkflowsConfig,
+) -> Vec<String> {
+    let fzf = get_fzf_builder(prompt, config.fzf())
+        .custom_args(["--multi"])
+        .build()
+        .unwrap();
+
+    fzf_wrapped::run_with_output(fzf, options.to_vec())
+        .unwrap_or_default()
+        .lines()
+        .filter(|line| !line.is_empty())
+        .map(|line| line.to_string())
+        .collect()
+}
+
 /// Gets the users Fzf instance, as defined by their config
 ///
 /// # Parameters
@@ -151,14 +383,29 @@ pub fn get_project_dir(config: &WorkflowsConfig) -> Option<String> {
 ///
 /// An [`Fzf`] instance
 fn get_fzf_instance(prompt: impl Into<String>, config: FzfConfig) -> Fzf {
-    fzf_wrapped::Fzf::builder()
+    get_fzf_builder(prompt, config).build().unwrap()
+}
+
+/// Gets a builder for the users Fzf instance, so options can be added before it's built
+///
+/// # Parameters
+///
+/// - `prompt` The prompt for fzf to have
+/// - `config` The user's definied fzf config
+///
+/// # Returns
+///
+/// An [`FzfBuilder`] with the user's options set
+fn get_fzf_builder(prompt: impl Into<String>, config: FzfConfig) -> FzfBuilder {
+    let mut builder = Fzf::builder();
+    builder
         .prompt(prompt)
         .pointer(config.pointer())
         .color(config.theme())
         .border(config.border())
         .ansi(true)
         .layout(config.layout())
-        .border_label(config.border_label())
-        .build()
-        .unwrap()
+        .border_label(config.border_label());
+
+    builder
 }
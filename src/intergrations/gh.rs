@@ -1,10 +1,16 @@
 //! This module contains the code for intergrating with the `gh` cli tool
 
 use std::io;
+use std::path::Path;
 use std::process::{Command, Stdio};
 
 use crate::repo::Repo;
 
+/// Creates a command for running `gh`
+fn gh() -> Command {
+    Command::new("gh")
+}
+
 /// Clones a repo using `gh`, streaming its output to stdout.
 ///
 /// **Blocks execution until finished**
@@ -13,17 +19,65 @@ use crate::repo::Repo;
 ///
 /// - `repo`        The repo to clone
 /// - `project_dir` The directory to clone the project to
+///
+/// # Returns
+///
+/// An error if the repo couldn't be cloned
 pub fn clone_repo(repo: &Repo, project_dir: String) -> io::Result<()> {
     let clone_dir = dirs::home_dir()
         .expect("couldn't get home dir")
         .join(project_dir);
-    let mut command = Command::new("gh")
+
+    clone_repo_with(gh, &repo.name(), &clone_dir)
+}
+
+/// Clones a repo using the given `gh` command. See [`clone_repo`]
+fn clone_repo_with(gh: impl Fn() -> Command, name: &str, clone_dir: &Path) -> io::Result<()> {
+    let status = gh()
         .current_dir(clone_dir)
-        .args(["repo", "clone", &repo.name()])
+        .args(["repo", "clone", name])
         .stdout(Stdio::piped())
-        .spawn()?;
-    command.wait().expect("Failed to wait on clone");
-    Ok(())
+        .status()?;
+
+    match status.success() {
+        true => Ok(()),
+        false => Err(io::Error::other(format!("gh repo clone {} failed", name))),
+    }
+}
+
+/// Gets the url a repo is cloned from, using the user's preferred git protocol
+///
+/// # Parameters
+///
+/// - `repo` The repo to get the url of
+///
+/// # Returns
+///
+/// `None` if `gh` couldn't find the repo
+pub fn clone_url(repo: &Repo) -> Option<String> {
+    clone_url_with(gh, &repo.name())
+}
+
+/// Gets the clone url using the given `gh` command. See [`clone_url`]
+fn clone_url_with(gh: impl Fn() -> Command, name: &str) -> Option<String> {
+    let protocol = gh()
+        .args(["config", "get", "git_protocol"])
+        .output()
+        .ok()
+        .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string());
+
+    let field = match protocol.as_deref() {
+        Some("ssh") => ".sshUrl",
+        _ => ".url + \".git\"",
+    };
+
+    let output = gh()
+        .args(["repo", "view", name, "--json", "url,sshUrl", "--jq", field])
+        .output()
+        .ok()?;
+
+    let url = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (output.status.success() && !url.is_empty()).then_some(url)
 }
 
 /// Gets the list of repos from the "gh repo list" command output, filtering out local projects
@@ -37,10 +91,12 @@ pub fn clone_repo(repo: &Repo, project_dir: String) -> io::Result<()> {
 ///
 /// A vec of repo structs
 pub fn get_gh_repos(local_projects: &[Repo]) -> Vec<Repo> {
-    let output = Command::new("gh")
-        .args(["repo", "list", "--limit", "1000"])
-        .output()
-        .ok();
+    gh_repos_with(gh, local_projects)
+}
+
+/// Gets the list of repos using the given `gh` command. See [`get_gh_repos`]
+fn gh_repos_with(gh: impl Fn() -> Command, local_projects: &[Repo]) -> Vec<Repo> {
+    let output = gh().args(["repo", "list", "--limit", "1000"]).output().ok();
 
     if let Some(output) = output {
         if String::from_utf8_lossy(&output.stderr).contains("error connecting") {
@@ -67,3 +123,307 @@ pub fn get_gh_repos(local_projects: &[Repo]) -> Vec<Repo> {
 
     vec![]
 }
+
+/// Gets the template repos owned by the given users and organisations
+///
+/// # Parameters
+///
+/// - `owners` The users and organisations to list template repos from
+///
+/// # Returns
+///
+/// The template repos in the format `owner/repo`
+pub fn get_template_repos(owners: &[String]) -> Vec<String> {
+    template_repos_with(gh, owners)
+}
+
+/// Gets the template repos using the given `gh` command. See [`get_template_repos`]
+fn template_repos_with(gh: impl Fn() -> Command, owners: &[String]) -> Vec<String> {
+    owners
+        .iter()
+        .filter_map(|owner| {
+            let output = gh()
+                .args(["repo", "list", owner, "--limit", "1000"])
+                .args(["--json", "nameWithOwner,isTemplate"])
+                .args(["--jq", ".[] | select(.isTemplate) | .nameWithOwner"])
+                .output()
+                .ok()?;
+
+            match output.status.success() {
+                true => Some(String::from_utf8_lossy(&output.stdout).to_string()),
+                false => None,
+            }
+        })
+        .flat_map(|stdout| {
+            stdout
+                .lines()
+                .map(|line| line.trim().to_string())
+                .filter(|line| line.contains('/'))
+                .collect::<Vec<String>>()
+        })
+        .collect()
+}
+
+/// Creates a repo on GitHub from a template repo, and clones it into the projects directory.
+///
+/// **Blocks execution until finished**
+///
+/// # Parameters
+///
+/// - `name`          The name of the repo to create
+/// - `template_repo` The template repo to create the repo from, e.g. `owner/template`
+/// - `projects_dir`  The projects directory to clone the repo into
+/// - `visibility`    The visibility of the created repo, e.g. `private`
+///
+/// # Returns
+///
+/// Whether the repo was created
+pub fn create_from_template(
+    name: &str,
+    template_repo: &str,
+    projects_dir: &str,
+    visibility: &str,
+) -> io::Result<bool> {
+    let clone_dir = dirs::home_dir()
+        .expect("couldn't get home dir")
+        .join(projects_dir);
+
+    let mut command = gh();
+    command.current_dir(clone_dir);
+
+    create_from_template_with(command, name, template_repo, visibility)
+}
+
+/// Creates a repo from a template using the given `gh` command. See [`create_from_template`]
+fn create_from_template_with(
+    mut gh: Command,
+    name: &str,
+    template_repo: &str,
+    visibility: &str,
+) -> io::Result<bool> {
+    let status = gh
+        .args(["repo", "create", name])
+        .args(["--template", template_repo])
+        .arg(format!("--{}", visibility))
+        .arg("--clone")
+        .status()?;
+
+    Ok(status.success())
+}
+
+/// Creates a repo on GitHub from a local project and pushes it, adding the repo as the `origin`
+/// remote.
+///
+/// **Blocks execution until finished**
+///
+/// # Parameters
+///
+/// - `project_root` The root of the local project, which must be a git repo
+/// - `name`         The name of the repo to create
+/// - `visibility`   The visibility of the created repo, e.g. `private`
+/// - `description`  The description of the created repo
+///
+/// # Returns
+///
+/// Whether the repo was created
+pub fn publish_repo(
+    project_root: &Path,
+    name: &str,
+    visibility: &str,
+    description: &str,
+) -> io::Result<bool> {
+    let mut command = gh();
+    command.current_dir(project_root);
+
+    publish_repo_with(command, name, visibility, description)
+}
+
+/// Publishes a repo using the given `gh` command. See [`publish_repo`]
+fn publish_repo_with(
+    mut gh: Command,
+    name: &str,
+    visibility: &str,
+    description: &str,
+) -> io::Result<bool> {
+    gh.args(["repo", "create", name])
+        .args(["--source", ".", "--push"])
+        .arg(format!("--{}", visibility));
+
+    if !description.is_empty() {
+        gh.args(["--description", description]);
+    }
+
+    Ok(gh.status()?.success())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs, os::unix::fs::PermissionsExt, path::Path, process::Command};
+
+    use super::{
+        clone_repo_with, clone_url_with, create_from_template_with, gh_repos_with,
+        publish_repo_with, template_repos_with,
+    };
+    use crate::{repo::Repo, test_utils::TempDir};
+
+    /// Creates a fake `gh` binary in a directory of its own, running the given script
+    fn fake_gh(test_name: &str, script: &str) -> TempDir {
+        let dir = TempDir::new(&format!("gh-{}", test_name));
+
+        let gh = dir.join("gh");
+        fs::write(&gh, format!("#!/bin/sh\n{}", script)).unwrap();
+        fs::set_permissions(&gh, fs::Permissions::from_mode(0o755)).unwrap();
+
+        dir
+    }
+
+    /// Creates a `gh` command that finds the fake `gh` first on its PATH
+    fn gh_on_path(fake_dir: &Path) -> Command {
+        let path = format!(
+            "{}:{}",
+            fake_dir.display(),
+            env::var("PATH").unwrap_or_default()
+        );
+
+        let mut command = Command::new("gh");
+        command.env("PATH", path);
+        command
+    }
+
+    #[test]
+    fn gh_repos_filters_local_projects() {
+        let fake_dir = fake_gh(
+            "repos",
+            "printf 'me/workflows\\tA cli tool\\tpublic\\nme/dotfiles\\t\\tprivate\\n'",
+        );
+
+        let local_projects = vec![Repo::new("dotfiles", true, Some("Projects/"))];
+
+        let repos = gh_repos_with(|| gh_on_path(&fake_dir), &local_projects);
+
+        assert_eq!(repos, vec![Repo::new("workflows", false, None)]);
+    }
+
+    #[test]
+    fn clone_url_uses_git_protocol() {
+        let script = |protocol: &str| {
+            format!(
+                "case \"$1\" in\n\
+                 config) echo {} ;;\n\
+                 repo) [ \"$7\" = .sshUrl ] && echo git@github.com:me/$3.git \
+                 || echo https://github.com/me/$3.git ;;\n\
+                 esac",
+                protocol
+            )
+        };
+
+        let https = fake_gh("clone-url-https", &script("https"));
+        assert_eq!(
+            clone_url_with(|| gh_on_path(&https), "workflows"),
+            Some("https://github.com/me/workflows.git".to_string())
+        );
+
+        let ssh = fake_gh("clone-url-ssh", &script("ssh"));
+        assert_eq!(
+            clone_url_with(|| gh_on_path(&ssh), "workflows"),
+            Some("git@github.com:me/workflows.git".to_string())
+        );
+
+        let missing = fake_gh("clone-url-missing", "exit 1");
+        assert_eq!(clone_url_with(|| gh_on_path(&missing), "workflows"), None);
+    }
+
+    #[test]
+    fn clone_repo_reports_failures() {
+        let cloned = fake_gh("clone", "mkdir \"$3\"");
+        clone_repo_with(|| gh_on_path(&cloned), "workflows", &cloned).unwrap();
+        assert!(cloned.join("workflows").is_dir());
+
+        let failed = fake_gh("clone-failed", "exit 1");
+        assert!(clone_repo_with(|| gh_on_path(&failed), "workflows", &failed).is_err());
+    }
+
+    #[test]
+    fn template_repos_works() {
+        let fake_dir = fake_gh(
+            "templates",
+            "[ \"$3\" = my-org ] && printf 'my-org/rust-template\\nmy-org/go-template\\n'\nexit 0",
+        );
+
+        let owners = vec!["my-org".to_string(), "someone-else".to_string()];
+
+        let repos = template_repos_with(|| gh_on_path(&fake_dir), &owners);
+
+        assert_eq!(repos, vec!["my-org/rust-template", "my-org/go-template"]);
+    }
+
+    #[test]
+    fn template_repos_skips_failed_owners() {
+        let fake_dir = fake_gh("templates-failed", "echo 'Could not resolve' >&2\nexit 1");
+
+        let repos = template_repos_with(|| gh_on_path(&fake_dir), &["my-org".to_string()]);
+
+        assert!(repos.is_empty());
+    }
+
+    #[test]
+    fn create_from_template_passes_arguments() {
+        let fake_dir = fake_gh("create", "echo \"$@\" > \"$(dirname \"$0\")/args\"");
+
+        let created = create_from_template_with(
+            gh_on_path(&fake_dir),
+            "new-project",
+            "my-org/rust-template",
+            "private",
+        )
+        .unwrap();
+
+        assert!(created);
+        assert_eq!(
+            fs::read_to_string(fake_dir.join("args")).unwrap(),
+            "repo create new-project --template my-org/rust-template --private --clone\n"
+        );
+    }
+
+    #[test]
+    fn create_from_template_reports_failure() {
+        let fake_dir = fake_gh("create-failed", "exit 1");
+
+        let created =
+            create_from_template_with(gh_on_path(&fake_dir), "new-project", "a/b", "public")
+                .unwrap();
+
+        assert!(!created);
+    }
+
+    #[test]
+    fn publish_repo_passes_arguments() {
+        let fake_dir = fake_gh("publish", "echo \"$@\" > \"$(dirname \"$0\")/args\"");
+
+        let published = publish_repo_with(
+            gh_on_path(&fake_dir),
+            "new-project",
+            "public",
+            "A new project",
+        )
+        .unwrap();
+
+        assert!(published);
+        assert_eq!(
+            fs::read_to_string(fake_dir.join("args")).unwrap(),
+            "repo create new-project --source . --push --public --description A new project\n"
+        );
+    }
+
+    #[test]
+    fn publish_repo_skips_empty_description() {
+        let fake_dir = fake_gh("publish-empty", "echo \"$@\" > \"$(dirname \"$0\")/args\"");
+
+        publish_repo_with(gh_on_path(&fake_dir), "new-project", "private", "").unwrap();
+
+        assert_eq!(
+            fs::read_to_string(fake_dir.join("args")).unwrap(),
+            "repo create new-project --source . --push --private\n"
+        );
+    }
+}
@@ -1,23 +1,30 @@
 use std::{
-    io,
-    process::{Command, Stdio},
+    env, fs, io,
+    os::unix::process::CommandExt,
+    path::{Path, PathBuf},
+    process::{self, Command, Stdio},
+    sync::atomic::{AtomicUsize, Ordering},
+    thread,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
 };
 
-use crate::{config::WorkflowsConfig, repo::Repo};
+use crate::{config::WorkflowsConfig, glob};
 
 use super::fzf::get_project_dir;
 
-/// Represents the possible outcomes of the repo_pushed() function
-pub enum PushedResult {
-    Status(bool),
-    NoConnection,
+/// Creates a command for running `git`. The locale is fixed so git's output doesn't depend on
+/// the user's language
+fn git() -> Command {
+    let mut command = Command::new("git");
+    command.env("LC_ALL", "C");
+    command
 }
 
 /// Attempts to clone the given repo at the url passed in
 ///
 /// # Returns
 ///
-/// An IO error if the repo doesn't exist, otherwise the selected project_dir
+/// An IO error if the repo couldn't be cloned, otherwise the selected project_dir
 pub fn clone_repo(url: &str, config: &WorkflowsConfig) -> io::Result<String> {
     let project_dir = get_project_dir(config).expect("Failed to get a directory");
 
@@ -25,49 +32,1051 @@ pub fn clone_repo(url: &str, config: &WorkflowsConfig) -> io::Result<String> {
         .expect("Failed to get home dir")
         .join(project_dir.clone());
 
-    let mut command = Command::new("git")
-        .current_dir(clone_dir.clone())
+    clone_into(url, &clone_dir)?;
+    Ok(project_dir)
+}
+
+/// Clones a repo into a directory, failing if git does
+///
+/// # Parameters
+///
+/// - `url`       The url of the repo
+/// - `clone_dir` The directory to clone the repo into
+fn clone_into(url: &str, clone_dir: &Path) -> io::Result<()> {
+    let status = git()
+        .current_dir(clone_dir)
         .args(["clone", url])
         .stdout(Stdio::piped())
+        .status()?;
+
+    match status.success() {
+        true => Ok(()),
+        false => Err(io::Error::other(format!("git clone {} failed", url))),
+    }
+}
+
+/// Gets a value from the user's git config
+///
+/// # Parameters
+///
+/// - `key` The key to look up, e.g. `user.name`
+///
+/// # Returns
+///
+/// `None` if git couldn't be run or the key isn't set
+pub fn config_value(key: &str) -> Option<String> {
+    let output = git().args(["config", key]).output().ok()?;
+
+    if !output.status.success() {
+        return None;
+    }
+
+    Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Gets the url of one of a repo's remotes
+///
+/// # Parameters
+///
+/// - `dir`    The root of the repo
+/// - `remote` The name of the remote, e.g. `origin`
+///
+/// # Returns
+///
+/// `None` if the remote doesn't exist or git couldn't be run
+pub fn remote_url(dir: &Path, remote: &str) -> Option<String> {
+    let output = git()
+        .current_dir(dir)
+        .args(["remote", "get-url", remote])
+        .output()
+        .ok()?;
+
+    if !output.status.success() {
+        return None;
+    }
+
+    Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Checks if the given directory is the root of a git repo
+///
+/// # Parameters
+///
+/// - `dir` The directory to check
+pub fn is_repo(dir: &Path) -> bool {
+    dir.join(".git").exists()
+}
+
+/// Initialises a git repo in the given directory
+///
+/// # Parameters
+///
+/// - `dir` The directory to initialise the repo in
+///
+/// # Returns
+///
+/// Whether the repo was initialised
+pub fn init(dir: &Path) -> io::Result<bool> {
+    let status = git()
+        .current_dir(dir)
+        .arg("init")
+        .stdout(Stdio::null())
+        .status()?;
+
+    Ok(status.success())
+}
+
+/// Stages every change in the repo and commits it. If the repo doesn't have any commits yet, an
+/// empty commit is made when there is nothing to commit
+///
+/// # Parameters
+///
+/// - `dir`     The root of the repo
+/// - `message` The commit message
+///
+/// # Returns
+///
+/// Whether a commit was made, or there was nothing to commit
+pub fn commit_all(dir: &Path, message: &str) -> io::Result<bool> {
+    let staged = git().current_dir(dir).args(["add", "-A"]).status()?;
+
+    if !staged.success() {
+        return Ok(false);
+    }
+
+    let has_commits = git()
+        .current_dir(dir)
+        .args(["rev-parse", "--verify", "--quiet", "HEAD"])
+        .stdout(Stdio::null())
+        .status()?
+        .success();
+
+    let nothing_staged = git()
+        .current_dir(dir)
+        .args(["diff", "--cached", "--quiet"])
+        .status()?
+        .success();
+
+    if has_commits && nothing_staged {
+        return Ok(true);
+    }
+
+    let status = git()
+        .current_dir(dir)
+        .args(["commit", "--quiet", "--allow-empty", "-m", message])
+        .status()?;
+
+    Ok(status.success())
+}
+
+/// Checks if the repo has a remote with the given name
+///
+/// # Parameters
+///
+/// - `dir`    The root of the repo
+/// - `remote` The name of the remote, e.g. `origin`
+pub fn has_remote(dir: &Path, remote: &str) -> bool {
+    remote_url(dir, remote).is_some()
+}
+
+/// The outcome of fetching a repo's remotes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FetchResult {
+    Fetched,
+    Failed,
+    TimedOut,
+}
+
+/// Fetches every remote of a repo, giving up once the timeout has passed. git is never allowed
+/// to prompt for credentials, as it would block until the timeout.
+///
+/// git is run in its own process group, so the `ssh` and `git-upload-pack` processes it starts
+/// are killed with it when the fetch times out, rather than holding the connection open
+///
+/// **Blocks execution until finished**
+///
+/// # Parameters
+///
+/// - `dir`     The root of the repo
+/// - `timeout` How long to wait for the fetch to finish
+pub fn fetch(dir: &Path, timeout: Duration) -> io::Result<FetchResult> {
+    let start = Instant::now();
+
+    let mut child = git()
+        .current_dir(dir)
+        .args(["fetch", "--all", "--prune", "--quiet"])
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .process_group(0)
         .spawn()?;
 
-    command.wait()?;
-    Ok(project_dir)
+    loop {
+        if let Some(status) = child.try_wait()? {
+            return Ok(match status.success() {
+                true => FetchResult::Fetched,
+                false => FetchResult::Failed,
+            });
+        }
+
+        if start.elapsed() >= timeout {
+            // The process group has the same id as git, as git leads it
+            let killed_group = Command::new("kill")
+                .args(["-KILL", "--", &format!("-{}", child.id())])
+                .stderr(Stdio::null())
+                .status()
+                .is_ok_and(|status| status.success());
+            if !killed_group {
+                child.kill()?;
+            }
+            child.wait()?;
+            return Ok(FetchResult::TimedOut);
+        }
+
+        thread::sleep(Duration::from_millis(50));
+    }
+}
+
+/// Gets when a repo's remote-tracking refs were last updated by a fetch or pull
+///
+/// # Parameters
+///
+/// - `dir` The root of the repo
+///
+/// # Returns
+///
+/// `None` if the repo has never been fetched since it was cloned
+pub fn last_fetched(dir: &Path) -> Option<SystemTime> {
+    let git_dir = git_output(dir, &["rev-parse", "--git-dir"]).ok()?;
+
+    dir.join(git_dir.trim())
+        .join("FETCH_HEAD")
+        .metadata()
+        .ok()?
+        .modified()
+        .ok()
 }
 
-/// Checks if the repo has every commit pushed
+/// Gets when the most recent commit on any branch was made
 ///
-/// Always returns false if the user is not connected to the internet
-pub fn repo_pushed(repo: &Repo) -> io::Result<PushedResult> {
-    let repo_dir = repo
-        .get_project_root()
-        .expect("Failed to get the projects root");
+/// # Parameters
+///
+/// - `dir` The root of the repo
+///
+/// # Returns
+///
+/// `None` if the repo doesn't have any commits
+pub fn last_commit(dir: &Path) -> Option<SystemTime> {
+    let timestamp = git_output(
+        dir,
+        &[
+            "for-each-ref",
+            "--sort=-committerdate",
+            "--count=1",
+            "--format=%(committerdate:raw)",
+            "refs/heads",
+        ],
+    )
+    .ok()?;
+
+    // The raw date is the seconds since the epoch, followed by the timezone
+    let seconds = timestamp.split_whitespace().next()?.parse().ok()?;
+    Some(UNIX_EPOCH + Duration::from_secs(seconds))
+}
+
+/// The state of a repo's working tree and current branch
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GitStatus {
+    /// The current branch, `None` if the HEAD is detached
+    head: Option<String>,
+    /// Tracked files with staged, unstaged or conflicting changes
+    changed: Vec<String>,
+    /// Untracked files that aren't ignored. Untracked directories are listed once
+    untracked: Vec<String>,
+    /// Ignored files. Ignored directories are listed once
+    ignored: Vec<String>,
+}
+
+impl GitStatus {
+    /// Parses the output of `git status --porcelain=v2 --branch -z`. Entries are separated by
+    /// NUL characters, so paths are never quoted
+    ///
+    /// # Parameters
+    ///
+    /// - `output` The output of git status
+    pub fn parse(output: &str) -> Self {
+        let mut status = Self::default();
+        let mut entries = output.split('\0').filter(|entry| !entry.is_empty());
 
-    let output = Command::new("git")
-        .current_dir(repo_dir)
-        .args(["push", "-n"])
-        .output()?;
+        while let Some(entry) = entries.next() {
+            let (kind, rest) = entry.split_once(' ').unwrap_or((entry, ""));
 
-    // If there is no connection, return NoConnection
-    if String::from_utf8_lossy(&output.stderr).contains("fatal: Could not read from remote repo") {
-        return Ok(PushedResult::NoConnection);
+            match kind {
+                "#" => match rest.split_once(' ') {
+                    Some(("branch.head", head)) if head != "(detached)" => {
+                        status.head = Some(head.to_string())
+                    }
+                    _ => {}
+                },
+                // Ordinary changes have 7 fields before the path, renames and copies have an
+                // extra score field and are followed by an entry with the original path, while
+                // unmerged changes have 9 fields before the path
+                "1" => status.changed.extend(nth_field(rest, 7)),
+                "2" => {
+                    status.changed.extend(nth_field(rest, 8));
+                    entries.next();
+                }
+                "u" => status.changed.extend(nth_field(rest, 9)),
+                "?" => status.untracked.push(rest.to_string()),
+                "!" => status.ignored.push(rest.to_string()),
+                _ => {}
+            }
+        }
+
+        status
+    }
+
+    /// The current branch, `None` if the HEAD is detached
+    pub fn head(&self) -> Option<&str> {
+        self.head.as_deref()
+    }
+
+    /// Tracked files with staged, unstaged or conflicting changes
+    pub fn changed(&self) -> &[String] {
+        self.changed.as_ref()
     }
 
-    Ok(PushedResult::Status(
-        String::from_utf8_lossy(&output.stderr).contains("up-to-date"),
-    ))
+    /// Untracked files that aren't ignored. Untracked directories are listed once
+    pub fn untracked(&self) -> &[String] {
+        self.untracked.as_ref()
+    }
+
+    /// Ignored files. Ignored directories are listed once
+    pub fn ignored(&self) -> &[String] {
+        self.ignored.as_ref()
+    }
 }
 
-/// Checks if the repo has a clean working tree
-pub fn repo_clean_tree(repo: &Repo) -> io::Result<bool> {
-    let repo_dir = repo
-        .get_project_root()
-        .expect("Failed to get the project root");
+/// Gets everything after the nth space separated field, which is where paths start in
+/// `git status --porcelain=v2`
+fn nth_field(line: &str, n: usize) -> Option<String> {
+    line.splitn(n + 1, ' ').nth(n).map(|path| path.to_string())
+}
+
+/// Gets the status of a repo, including its ignored files
+///
+/// # Parameters
+///
+/// - `dir` The root of the repo
+pub fn status(dir: &Path) -> io::Result<GitStatus> {
+    Ok(GitStatus::parse(&git_output(
+        dir,
+        &[
+            "status",
+            "--porcelain=v2",
+            "--branch",
+            "-z",
+            "--untracked-files=normal",
+            "--ignored=traditional",
+        ],
+    )?))
+}
+
+/// The state of a local branch compared to its upstream
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BranchStatus {
+    /// The name of the branch
+    name: String,
+    /// The branch's upstream, e.g. `origin/main`
+    upstream: Option<String>,
+    /// The number of commits on the branch that aren't on its upstream
+    ahead: u32,
+    /// The number of commits on the upstream that aren't on the branch
+    behind: u32,
+    /// Whether the upstream branch has been deleted
+    gone: bool,
+}
+
+impl BranchStatus {
+    /// The name of the branch
+    pub fn name(&self) -> &str {
+        self.name.as_ref()
+    }
 
-    let output = Command::new("git")
-        .current_dir(repo_dir)
-        .args(["status"])
-        .output()?;
+    /// The branch's upstream, e.g. `origin/main`
+    pub fn upstream(&self) -> Option<&str> {
+        self.upstream.as_deref()
+    }
+
+    /// The number of commits on the branch that aren't on its upstream
+    pub fn ahead(&self) -> u32 {
+        self.ahead
+    }
+
+    /// The number of commits on the upstream that aren't on the branch
+    pub fn behind(&self) -> u32 {
+        self.behind
+    }
+
+    /// Whether the upstream branch has been deleted
+    pub fn gone(&self) -> bool {
+        self.gone
+    }
+
+    /// Whether every commit on the branch exists on its upstream
+    pub fn pushed(&self) -> bool {
+        self.upstream.is_some() && !self.gone && self.ahead == 0
+    }
+}
+
+/// An operation that has been started in a repo but not finished
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operation {
+    Merge,
+    Rebase,
+    CherryPick,
+    Revert,
+    Bisect,
+}
+
+impl Operation {
+    /// The name of the operation, e.g. `cherry-pick`
+    pub fn name(&self) -> &'static str {
+        match self {
+            Operation::Merge => "merge",
+            Operation::Rebase => "rebase",
+            Operation::CherryPick => "cherry-pick",
+            Operation::Revert => "revert",
+            Operation::Bisect => "bisect",
+        }
+    }
+}
+
+/// Everything in a repo that would be lost if the repo was deleted
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SafetyReport {
+    /// The status of the working tree
+    status: GitStatus,
+    /// Every local branch
+    branches: Vec<BranchStatus>,
+    /// The stash entries, e.g. `stash@{0}: WIP on main: 1234567 Add thing`
+    stashes: Vec<String>,
+    /// Ignored files matching the user's precious files
+    precious: Vec<String>,
+    /// Operations that have been started but not finished
+    operations: Vec<Operation>,
+    /// When the remote-tracking refs the branches are compared against were last fetched
+    last_fetched: Option<SystemTime>,
+}
+
+impl SafetyReport {
+    /// The status of the working tree
+    pub fn status(&self) -> &GitStatus {
+        &self.status
+    }
+
+    /// Every local branch
+    pub fn branches(&self) -> &[BranchStatus] {
+        self.branches.as_ref()
+    }
+
+    /// The stash entries, e.g. `stash@{0}: WIP on main: 1234567 Add thing`
+    pub fn stashes(&self) -> &[String] {
+        self.stashes.as_ref()
+    }
+
+    /// Ignored files matching the user's precious files
+    pub fn precious(&self) -> &[String] {
+        self.precious.as_ref()
+    }
+
+    /// Operations that have been started but not finished
+    pub fn operations(&self) -> &[Operation] {
+        self.operations.as_ref()
+    }
+
+    /// When the remote-tracking refs the branches are compared against were last fetched,
+    /// `None` if they haven't been fetched since the repo was cloned
+    pub fn last_fetched(&self) -> Option<SystemTime> {
+        self.last_fetched
+    }
+
+    /// Whether any branch has commits that aren't on its upstream
+    pub fn unpushed(&self) -> bool {
+        self.branches.iter().any(|branch| !branch.pushed())
+    }
+
+    /// Whether there are uncommitted changes, untracked files or stash entries
+    pub fn dirty(&self) -> bool {
+        !self.status.changed.is_empty()
+            || !self.status.untracked.is_empty()
+            || !self.stashes.is_empty()
+    }
+}
 
-    Ok(String::from_utf8_lossy(&output.stdout).contains("nothing to commit, working tree clean"))
+/// Builds a report of everything in a repo that isn't safely stored elsewhere. Branches are
+/// compared against the remote-tracking refs from the last fetch, so no network access is needed
+///
+/// # Parameters
+///
+/// - `dir`      The root of the repo
+/// - `precious` Globs matching ignored files that should still be reported, e.g. `.env*`
+pub fn safety_report(dir: &Path, precious: &[String]) -> io::Result<SafetyReport> {
+    let branches = parse_branches(&git_output(
+        dir,
+        &[
+            "for-each-ref",
+            "--format=%(refname:short)%09%(upstream:short)%09%(upstream:track)",
+            "refs/heads",
+        ],
+    )?);
+
+    let stashes = git_lines(dir, &["stash", "list"])?;
+
+    let status = status(dir)?;
+
+    let precious = precious_files(dir, status.ignored(), precious)?;
+
+    let git_dir = dir.join(git_output(dir, &["rev-parse", "--git-dir"])?.trim());
+    let operations = [
+        ("MERGE_HEAD", Operation::Merge),
+        ("rebase-merge", Operation::Rebase),
+        ("rebase-apply", Operation::Rebase),
+        ("CHERRY_PICK_HEAD", Operation::CherryPick),
+        ("REVERT_HEAD", Operation::Revert),
+        ("BISECT_LOG", Operation::Bisect),
+    ]
+    .into_iter()
+    .filter(|(file, _)| git_dir.join(file).exists())
+    .map(|(_, operation)| operation)
+    .fold(vec![], |mut operations, operation| {
+        if !operations.contains(&operation) {
+            operations.push(operation);
+        }
+        operations
+    });
+
+    Ok(SafetyReport {
+        status,
+        branches,
+        stashes,
+        precious,
+        operations,
+        last_fetched: last_fetched(dir),
+    })
+}
+
+/// Writes a bundle of every ref in a repo, which can be cloned from to restore the repo
+///
+/// # Parameters
+///
+/// - `dir`    The root of the repo
+/// - `bundle` The file to write the bundle to
+///
+/// # Returns
+///
+/// Whether the bundle was written. Repos without any commits can't be bundled
+pub fn bundle(dir: &Path, bundle: &Path) -> io::Result<bool> {
+    let status = git()
+        .current_dir(dir)
+        .args(["bundle", "create", "--quiet"])
+        .arg(bundle)
+        .arg("--all")
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()?;
+
+    Ok(status.success())
+}
+
+/// Creates a binary patch of every uncommitted change in a repo, including untracked files that
+/// aren't ignored. A temporary index is used, so the repo's own index is left untouched
+///
+/// Ignored files aren't in the patch, so precious files have to be backed up separately
+///
+/// # Parameters
+///
+/// - `dir` The root of the repo
+///
+/// # Returns
+///
+/// An IO error if any of the git commands fail, as the patch would be missing changes
+pub fn uncommitted_patch(dir: &Path) -> io::Result<Vec<u8>> {
+    // The hash of the empty tree, which changes are compared against before the first commit
+    const EMPTY_TREE: &str = "4b825dc642cb6eb9a060e54bf8d69288fbee4904";
+
+    let index = TempIndex::new();
+    let with_index = |args: &[&str]| -> io::Result<Vec<u8>> {
+        let output = git()
+            .current_dir(dir)
+            .env("GIT_INDEX_FILE", &index.0)
+            .args(args)
+            .output()?;
+
+        match output.status.success() {
+            true => Ok(output.stdout),
+            false => Err(io::Error::other(format!(
+                "git {} failed: {}",
+                args.join(" "),
+                String::from_utf8_lossy(&output.stderr).trim()
+            ))),
+        }
+    };
+
+    let base = match with_index(&["rev-parse", "--verify", "--quiet", "HEAD"]) {
+        Ok(_) => "HEAD",
+        Err(_) => EMPTY_TREE,
+    };
+
+    with_index(&["read-tree", base])?;
+    with_index(&["add", "-A"])?;
+    with_index(&["diff", "--cached", "--binary", base])
+}
+
+/// A path for a temporary git index, unique to each call in the process, which is removed when
+/// dropped
+struct TempIndex(PathBuf);
+
+impl TempIndex {
+    fn new() -> Self {
+        static NEXT_INDEX: AtomicUsize = AtomicUsize::new(0);
+
+        Self(env::temp_dir().join(format!(
+            "workflows-patch-index-{}-{}",
+            process::id(),
+            NEXT_INDEX.fetch_add(1, Ordering::Relaxed)
+        )))
+    }
+}
+
+impl Drop for TempIndex {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.0);
+    }
+}
+
+/// Runs git in the given directory, returning its stdout
+///
+/// # Parameters
+///
+/// - `dir`  The directory to run git in
+/// - `args` The arguments to give git
+fn git_output(dir: &Path, args: &[&str]) -> io::Result<String> {
+    let output = git().current_dir(dir).args(args).output()?;
+
+    if !output.status.success() {
+        return Err(io::Error::other(format!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Runs git in the given directory, returning the non-empty lines of its stdout
+fn git_lines(dir: &Path, args: &[&str]) -> io::Result<Vec<String>> {
+    Ok(git_output(dir, args)?
+        .lines()
+        .filter(|line| !line.is_empty())
+        .map(|line| line.to_string())
+        .collect())
+}
+
+/// Parses the output of `git for-each-ref` in the format
+/// `%(refname:short)%09%(upstream:short)%09%(upstream:track)`
+fn parse_branches(output: &str) -> Vec<BranchStatus> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split('\t');
+            let name = fields.next().filter(|name| !name.is_empty())?;
+            let upstream = fields.next().filter(|upstream| !upstream.is_empty());
+            let track = fields.next().unwrap_or_default();
+
+            let mut branch = BranchStatus {
+                name: name.to_string(),
+                upstream: upstream.map(|upstream| upstream.to_string()),
+                ahead: 0,
+                behind: 0,
+                gone: false,
+            };
+
+            // The track is in the format `[ahead 1, behind 2]`, or `[gone]`
+            for part in track.trim_matches(['[', ']']).split(", ") {
+                match part.split_once(' ') {
+                    Some(("ahead", count)) => branch.ahead = count.parse().unwrap_or_default(),
+                    Some(("behind", count)) => branch.behind = count.parse().unwrap_or_default(),
+                    _ => branch.gone |= part == "gone",
+                }
+            }
+
+            Some(branch)
+        })
+        .collect()
+}
+
+/// Finds the ignored files matching the precious globs. git status lists ignored directories
+/// once, so the files inside the ones that aren't precious themselves are listed too, as a
+/// precious file such as `.env` can be inside an ignored directory. The globs are passed to git as
+/// pathspecs, so it only lists the files that could match rather than all of `node_modules`
+///
+/// # Parameters
+///
+/// - `dir`      The root of the repo
+/// - `ignored`  The ignored files and directories from git status
+/// - `precious` Globs matching precious files, e.g. `.env*`
+fn precious_files(dir: &Path, ignored: &[String], precious: &[String]) -> io::Result<Vec<String>> {
+    let mut found = vec![];
+
+    if precious.is_empty() {
+        return Ok(found);
+    }
+
+    let mut ignored_dirs = vec![];
+
+    for entry in ignored {
+        if is_precious(entry, precious) {
+            found.push(entry.clone());
+        } else if entry.ends_with('/') {
+            ignored_dirs.push(entry.as_str());
+        }
+    }
+
+    if ignored_dirs.is_empty() {
+        return Ok(found);
+    }
+
+    let pathspecs = precious_pathspecs(&ignored_dirs, precious);
+    let mut args = vec![
+        "ls-files",
+        "--others",
+        "--ignored",
+        "--exclude-standard",
+        "-z",
+        "--",
+    ];
+    args.extend(pathspecs.iter().map(|pathspec| pathspec.as_str()));
+
+    let files = git_output(dir, &args)?;
+
+    found.extend(
+        files
+            .split('\0')
+            .filter(|file| {
+                !file.is_empty()
+                    && ignored_dirs
+                        .iter()
+                        .any(|ignored_dir| file.starts_with(ignored_dir))
+                    && is_precious(file, precious)
+            })
+            .map(|file| file.to_string()),
+    );
+
+    Ok(found)
+}
+
+/// Builds the pathspecs matching every file inside the ignored directories that could be precious.
+/// git's wildcards in pathspecs match across `/`, like `*` in the precious globs, so the pathspecs
+/// can match a few more files than the globs, which are checked again afterwards
+///
+/// # Parameters
+///
+/// - `ignored_dirs` The ignored directories from git status, ending with `/`
+/// - `precious`     Globs matching precious files, e.g. `.env*`
+///
+/// # Returns
+///
+/// The pathspecs, e.g. `target/*.env*` for `target/` and `.env*`
+fn precious_pathspecs(ignored_dirs: &[&str], precious: &[String]) -> Vec<String> {
+    let mut pathspecs = vec![];
+
+    for glob in precious {
+        let glob = glob.trim_end_matches('/');
+        // Only `*` and `?` are wildcards in the precious globs, so anything else git would treat
+        // as one has to be escaped
+        let escaped_glob = escape_pathspec(glob, &['[', ']', '\\']);
+
+        if glob.contains('/') {
+            // Matched against the whole path, which a file name can't
+            pathspecs.push(escaped_glob);
+            continue;
+        }
+
+        for ignored_dir in ignored_dirs {
+            let ignored_dir = escape_pathspec(ignored_dir, &['*', '?', '[', ']', '\\']);
+            pathspecs.push(format!("{}*{}", ignored_dir, escaped_glob));
+        }
+    }
+
+    pathspecs
+}
+
+/// Escapes the characters git would treat as wildcards in a pathspec
+fn escape_pathspec(text: &str, special: &[char]) -> String {
+    let mut escaped = String::with_capacity(text.len());
+
+    for c in text.chars() {
+        if special.contains(&c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+
+    escaped
+}
+
+/// Checks whether an ignored file matches any of the precious globs, either by its path or its
+/// file name
+fn is_precious(file: &str, precious: &[String]) -> bool {
+    let path = file.trim_end_matches('/');
+    let file_name = path.rsplit('/').next().unwrap_or(path);
+
+    precious.iter().any(|glob| {
+        let glob = glob.trim_end_matches('/');
+        glob::matches(glob, path) || glob::matches(glob, file_name)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, thread, time::Duration};
+
+    use crate::test_utils::{self, git, TempDir};
+
+    use super::{
+        clone_into, commit_all, fetch, has_remote, init, is_precious, is_repo, last_commit,
+        last_fetched, parse_branches, precious_pathspecs, safety_report, uncommitted_patch,
+        BranchStatus, FetchResult, GitStatus, Operation,
+    };
+
+    #[test]
+    fn clone_into_reports_failures() {
+        let dir = TempDir::new("clone");
+        let origin = test_utils::temp_repo("clone-origin");
+        fs::write(origin.join("README.md"), "# origin").unwrap();
+        commit_all(&origin, "Initial commit").unwrap();
+
+        clone_into(&origin.to_string_lossy(), &dir).unwrap();
+        assert!(dir
+            .join(origin.file_name().unwrap())
+            .join("README.md")
+            .is_file());
+
+        // The repo is already cloned there
+        assert!(clone_into(&origin.to_string_lossy(), &dir).is_err());
+        assert!(clone_into(&dir.join("missing").to_string_lossy(), &dir).is_err());
+    }
+
+    /// `git status --porcelain=v2 --branch -z --ignored=traditional` with a modified file, a
+    /// renamed file with a space in its name, a conflict, an untracked file with a non-ASCII name
+    /// and ignored files
+    const STATUS_FIXTURE: &str = "\
+# branch.oid 1ceb5ac48e32646418a0cecbd33ad5113de8ee3e\0\
+# branch.head master\0\
+# branch.upstream origin/master\0\
+# branch.ab +1 -0\0\
+1 .M N... 100644 100644 100644 61780798228d17af2d34fce4cfbdf35556832472 61780798228d17af2d34fce4cfbdf35556832472 b\0\
+2 R. N... 100644 100644 100644 78981922613b2afb6025042ff6bd878ac1994e85 78981922613b2afb6025042ff6bd878ac1994e85 R100 c d\0a\0\
+u UU N... 100644 100644 100644 100644 257cc5642cb1a054f08cc83f2d943e56fd3ebe99 3bd1f0e29744a1f32b08d5650e62e2e62afb177c 5716ca5987cbf97d6bb54920bea6adde242d87e6 conflicted.rs\0\
+? naïve notes.md\0\
+! .env\0\
+! target/\0";
+
+    /// `git status --porcelain=v2 --branch -z` on a detached HEAD with a clean working tree
+    const DETACHED_STATUS_FIXTURE: &str = "\
+# branch.oid 1ceb5ac48e32646418a0cecbd33ad5113de8ee3e\0\
+# branch.head (detached)\0";
+
+    #[test]
+    fn init_and_commit_all_works() {
+        let dir = TempDir::new("git-commit");
+        fs::write(dir.join("README.md"), "# test").unwrap();
+
+        assert!(!is_repo(&dir));
+        assert!(init(&dir).unwrap());
+        assert!(is_repo(&dir));
+
+        // Making sure the commit works without a global git identity
+        test_utils::set_identity(&dir);
+
+        assert!(commit_all(&dir, "Initial commit").unwrap());
+        // Nothing left to commit
+        assert!(commit_all(&dir, "Second commit").unwrap());
+
+        let log = git(&dir, &["log", "--format=%s"]);
+        assert_eq!(String::from_utf8_lossy(&log.stdout), "Initial commit\n");
+
+        assert!(!has_remote(&dir, "origin"));
+    }
+
+    #[test]
+    fn parse_branches_works() {
+        let output = "\
+main\torigin/main\t
+feature\torigin/feature\t[ahead 2, behind 1]
+old\torigin/old\t[gone]
+local-only\t\t
+";
+
+        let branches = parse_branches(output);
+
+        assert_eq!(branches.len(), 4);
+        assert!(branches[0].pushed());
+
+        assert_eq!(branches[1].name(), "feature");
+        assert_eq!((branches[1].ahead(), branches[1].behind()), (2, 1));
+        assert!(!branches[1].pushed());
+
+        assert!(branches[2].gone());
+        assert!(!branches[2].pushed());
+
+        assert_eq!(
+            branches[3],
+            BranchStatus {
+                name: "local-only".to_string(),
+                upstream: None,
+                ahead: 0,
+                behind: 0,
+                gone: false,
+            }
+        );
+        assert!(!branches[3].pushed());
+    }
+
+    #[test]
+    fn is_precious_works() {
+        let precious = vec![".env*".to_string(), "secrets/".to_string()];
+
+        assert!(is_precious(".env", &precious));
+        assert!(is_precious("config/.env.local", &precious));
+        assert!(is_precious("secrets/", &precious));
+        assert!(!is_precious("target/", &precious));
+    }
+
+    #[test]
+    fn precious_pathspecs_work() {
+        let precious = vec![
+            ".env*".to_string(),
+            "config/*.key".to_string(),
+            "[a]".to_string(),
+        ];
+
+        assert_eq!(
+            precious_pathspecs(&["target/", "odd*dir/"], &precious),
+            vec![
+                "target/*.env*",
+                "odd\\*dir/*.env*",
+                "config/*.key",
+                "target/*\\[a\\]",
+                "odd\\*dir/*\\[a\\]",
+            ]
+        );
+    }
+
+    #[test]
+    fn safety_report_works() {
+        let dir = test_utils::temp_repo("git-safety");
+        let git = |args: &[&str]| git(&dir, args);
+
+        fs::write(dir.join(".gitignore"), ".env\nconfig/\ntarget/\n").unwrap();
+        git(&["add", "-A"]);
+        git(&["commit", "--quiet", "-m", "Initial commit"]);
+        git(&["branch", "feature"]);
+
+        fs::write(dir.join(".env"), "SECRET=1").unwrap();
+        fs::create_dir_all(dir.join("target")).unwrap();
+        fs::write(dir.join("target/build"), "").unwrap();
+        fs::create_dir_all(dir.join("config/local")).unwrap();
+        fs::write(dir.join("config/local/.env.local"), "SECRET=2").unwrap();
+        fs::write(dir.join("config/settings.toml"), "").unwrap();
+        fs::write(dir.join("naïve notes.txt"), "").unwrap();
+
+        fs::write(dir.join(".gitignore"), ".env\nconfig/\ntarget/\nwip\n").unwrap();
+        git(&["stash", "--quiet"]);
+        fs::write(dir.join("MERGE_HEAD"), "").unwrap();
+        fs::rename(dir.join("MERGE_HEAD"), dir.join(".git/MERGE_HEAD")).unwrap();
+
+        let report = safety_report(&dir, &[".env*".to_string()]).unwrap();
+
+        let names: Vec<&str> = report.branches().iter().map(|x| x.name()).collect();
+        assert_eq!(names, vec!["feature", "main"]);
+        assert!(report.branches().iter().all(|x| x.upstream().is_none()));
+        assert_eq!(report.stashes().len(), 1);
+        assert_eq!(report.status().untracked(), vec!["naïve notes.txt"]);
+        assert_eq!(report.status().changed(), Vec::<String>::new());
+        assert_eq!(report.precious(), vec![".env", "config/local/.env.local"]);
+        assert_eq!(report.operations(), vec![Operation::Merge]);
+
+        let since_last_commit = last_commit(&dir).unwrap().elapsed().unwrap_or_default();
+        assert!(since_last_commit < Duration::from_secs(60));
+    }
+
+    #[test]
+    fn uncommitted_patch_reports_failures() {
+        let dir = TempDir::new("git-patch");
+
+        // Without a repo, reading the tree into the index fails
+        assert!(uncommitted_patch(&dir).is_err());
+    }
+
+    #[test]
+    fn status_parse_works() {
+        let status = GitStatus::parse(STATUS_FIXTURE);
+
+        assert_eq!(status.head(), Some("master"));
+        assert_eq!(status.changed(), vec!["b", "c d", "conflicted.rs"]);
+        assert_eq!(status.untracked(), vec!["naïve notes.md"]);
+        assert_eq!(status.ignored(), vec![".env", "target/"]);
+    }
+
+    #[test]
+    fn status_parse_detached_works() {
+        let status = GitStatus::parse(DETACHED_STATUS_FIXTURE);
+
+        assert_eq!(status, GitStatus::default());
+        assert_eq!(status.head(), None);
+    }
+
+    #[test]
+    fn fetch_works() {
+        let base = TempDir::new("git-fetch");
+        let (remote, dir) = (base.join("remote"), base.join("local"));
+        fs::create_dir_all(&remote).unwrap();
+
+        git(&remote, &["init", "--quiet", "--bare"]);
+        git(&base, &["clone", "--quiet", "remote", "local"]);
+
+        assert_eq!(last_fetched(&dir), None);
+        assert_eq!(
+            fetch(&dir, Duration::from_secs(10)).unwrap(),
+            FetchResult::Fetched
+        );
+        assert!(last_fetched(&dir).is_some());
+
+        // Making the remote take longer to respond than the timeout
+        let pid_file = base.join("upload-pack.pid");
+        let upload_pack = format!("echo $$ > {}; sleep 5; git-upload-pack", pid_file.display());
+        git(&dir, &["config", "remote.origin.uploadpack", &upload_pack]);
+        assert_eq!(
+            fetch(&dir, Duration::from_millis(500)).unwrap(),
+            FetchResult::TimedOut
+        );
+
+        // The remote's process is killed along with git, rather than left running
+        let pid = fs::read_to_string(&pid_file).unwrap();
+        thread::sleep(Duration::from_millis(100));
+        let running = fs::read_to_string(format!("/proc/{}/stat", pid.trim()))
+            .is_ok_and(|stat| !stat.contains(") Z "));
+        assert!(!running);
+
+        git(&dir, &["config", "--unset", "remote.origin.uploadpack"]);
+        git(&dir, &["remote", "set-url", "origin", "/nonexistent"]);
+        assert_eq!(
+            fetch(&dir, Duration::from_secs(10)).unwrap(),
+            FetchResult::Failed
+        );
+    }
 }
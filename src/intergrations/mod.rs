@@ -4,6 +4,7 @@
 //! - `git`
 //! - `gh`
 //! - `tmux` & `tmuxinator`
+//! - `sh`
 
 pub mod fzf;
 
@@ -14,3 +15,5 @@ pub mod tmuxinator;
 pub mod tmux;
 
 pub mod git;
+
+pub mod sh;
@@ -0,0 +1,102 @@
+//! This module contains the logic for running shell commands on the user's behalf, such as the
+//! steps of a template
+
+use std::{
+    io,
+    path::Path,
+    process::{Command, ExitStatus, Stdio},
+    time::{Duration, Instant},
+};
+
+/// The result of a command run with [`run_streamed`]
+pub struct CommandOutcome {
+    status: ExitStatus,
+    elapsed: Duration,
+}
+
+impl CommandOutcome {
+    /// Whether the command exited successfully
+    pub fn success(&self) -> bool {
+        self.status.success()
+    }
+
+    /// The exit code of the command, `None` if it was killed by a signal
+    pub fn code(&self) -> Option<i32> {
+        self.status.code()
+    }
+
+    /// How long the command took to run
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+}
+
+/// Runs a command with `sh -c`, streaming its output live.
+///
+/// stdin, stdout and stderr are all inherited, so the command sees the user's terminal. This
+/// keeps progress bars, colours and prompts working, but means the output isn't captured.
+///
+/// **Blocks execution until finished**
+///
+/// # Parameters
+///
+/// - `command` The command to run
+/// - `dir`     The directory to run the command in
+/// - `envs`    Extra environment variables to give the command
+pub fn run_streamed(
+    command: &str,
+    dir: &Path,
+    envs: &[(String, String)],
+) -> io::Result<CommandOutcome> {
+    let start = Instant::now();
+
+    let status = Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .current_dir(dir)
+        .envs(envs.iter().map(|(key, value)| (key, value)))
+        .stdin(Stdio::inherit())
+        .stdout(Stdio::inherit())
+        .stderr(Stdio::inherit())
+        .status()?;
+
+    Ok(CommandOutcome {
+        status,
+        elapsed: start.elapsed(),
+    })
+}
+
+/// Formats a duration for displaying after a command finishes, e.g. `1.42s`
+pub fn format_elapsed(elapsed: Duration) -> String {
+    format!("{:.2}s", elapsed.as_secs_f64())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, time::Duration};
+
+    use super::{format_elapsed, run_streamed};
+
+    #[test]
+    fn run_streamed_reports_exit_code() {
+        let outcome = run_streamed("exit 3", &env::temp_dir(), &[]).unwrap();
+
+        assert!(!outcome.success());
+        assert_eq!(outcome.code(), Some(3));
+    }
+
+    #[test]
+    fn run_streamed_passes_envs() {
+        let envs = vec![("WORKFLOWS_TEST".to_string(), "value".to_string())];
+
+        let outcome =
+            run_streamed("test \"$WORKFLOWS_TEST\" = value", &env::temp_dir(), &envs).unwrap();
+
+        assert!(outcome.success());
+    }
+
+    #[test]
+    fn format_elapsed_works() {
+        assert_eq!(format_elapsed(Duration::from_millis(1420)), "1.42s");
+    }
+}
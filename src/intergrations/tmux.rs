@@ -1,9 +1,18 @@
 //! This module contains all logic for interacting with the `tmux` command line program
 
-use std::{env, io, process::Command};
+use std::{
+    env, io,
+    path::Path,
+    process::{Command, Stdio},
+};
 
 use crate::repo::Repo;
 
+/// Shells that panes sit in while they aren't running anything
+const SHELLS: [&str; 11] = [
+    "sh", "bash", "zsh", "fish", "dash", "ksh", "mksh", "csh", "tcsh", "nu", "elvish",
+];
+
 /// Gets a list of active tmux sessions
 ///
 /// # Returns
@@ -79,3 +88,106 @@ fn create_tmux_session(project: &Repo) -> io::Result<()> {
 
     attach_tmux_session(project.name())
 }
+
+/// Gets the command running in each pane of a tmux session
+///
+/// # Parameters
+///
+/// - `session_name` The session to look at
+///
+/// # Returns
+///
+/// The command running in the foreground of each pane, or `None` if there is no such session
+pub fn session_commands(session_name: &str) -> io::Result<Option<Vec<String>>> {
+    let output = Command::new("tmux")
+        .args(["list-panes", "-s", "-F", "#{pane_current_command}", "-t"])
+        .arg(format!("={}", session_name))
+        .stderr(Stdio::null())
+        .output();
+
+    let output = match output {
+        Ok(output) if output.status.success() => output,
+        // tmux isn't installed
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(err) => return Err(err),
+        // No server is running, or it has no such session
+        Ok(_) => return Ok(None),
+    };
+
+    Ok(Some(
+        String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .map(|command| command.to_string())
+            .collect(),
+    ))
+}
+
+/// Kills a tmux session
+///
+/// # Parameters
+///
+/// - `session_name` The session to kill
+///
+/// # Returns
+///
+/// Whether the session was killed
+pub fn kill_session(session_name: &str) -> io::Result<bool> {
+    let status = Command::new("tmux")
+        .arg("kill-session")
+        .arg("-t")
+        .arg(format!("={}", session_name))
+        .stderr(Stdio::null())
+        .status()?;
+
+    Ok(status.success())
+}
+
+/// Gets the name of the tmux session workflows is running in
+///
+/// # Returns
+///
+/// `None` if workflows isn't running inside tmux
+pub fn current_session() -> Option<String> {
+    env::var("TMUX").ok().filter(|tmux| !tmux.is_empty())?;
+    // Without a target, tmux picks a session itself when it isn't run from a client
+    let pane = env::var("TMUX_PANE").ok().filter(|pane| !pane.is_empty())?;
+
+    let output = Command::new("tmux")
+        .args(["display-message", "-p", "-t", &pane, "#S"])
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+
+    let session = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (output.status.success() && !session.is_empty()).then_some(session)
+}
+
+/// Whether a pane's command is just a shell, so nothing is lost by killing it
+///
+/// # Parameters
+///
+/// - `command` The command running in the pane
+pub fn is_shell(command: &str) -> bool {
+    // Login shells are shown with a leading dash
+    let command = command.trim_start_matches('-');
+
+    let user_shell = env::var("SHELL").ok();
+    let user_shell = user_shell
+        .as_deref()
+        .and_then(|shell| Path::new(shell).file_name()?.to_str());
+
+    SHELLS.contains(&command) || user_shell == Some(command)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::is_shell;
+
+    #[test]
+    fn is_shell_works() {
+        assert!(is_shell("zsh"));
+        assert!(is_shell("-bash"));
+        assert!(!is_shell("nvim"));
+        assert!(!is_shell("cargo"));
+    }
+}
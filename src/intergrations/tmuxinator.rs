@@ -15,6 +15,19 @@ fn tmuxinator_config_dir() -> PathBuf {
         .join("tmuxinator")
 }
 
+/// The path to a project's tmuxinator config
+///
+/// # Parameters
+///
+/// - `project` The project to get the config for
+///
+/// # Returns
+///
+/// A [`PathBuf`] leading to ~/.config/tmuxinator/<projectname>.yml
+pub fn tmuxinator_config_file(project: &Repo) -> PathBuf {
+    tmuxinator_config_dir().join(format!("{}.yml", project.name()))
+}
+
 /// Checks if the project already has a tmuxinator project
 ///
 /// # Parameters
@@ -1,13 +1,24 @@
 use std::{env, io};
 
+use intergrations::fzf::ProjectFilter;
+
 mod repo;
 
+mod variables;
+
+mod glob;
+
 mod intergrations;
 
 mod config;
 
 mod commands;
 
+mod state;
+
+#[cfg(test)]
+mod test_utils;
+
 fn main() -> io::Result<()> {
     let args: Vec<String> = env::args().collect();
 
@@ -20,7 +31,50 @@ fn main() -> io::Result<()> {
         return Ok(());
     }
 
-    let config = config::get_config().unwrap_or_default();
+    let mut config = config::get_config()
+        .unwrap_or_default()
+        .with_template_files();
+
+    if args.contains(&"--no-setup".to_string()) {
+        config = config.without_clone_setup();
+    }
+
+    if args.get(1).is_some_and(|x| x == "template") {
+        return commands::template_command(args.get(2).cloned(), args.get(3).cloned(), config);
+    }
+
+    if args.get(1).is_some_and(|x| x == "trash") {
+        return commands::trash_command(args.get(2).cloned(), args.get(3).cloned(), config);
+    }
+
+    if args.get(1).is_some_and(|x| x == "tag") {
+        return commands::tag_command(
+            args.get(2).cloned(),
+            args.get(3).cloned(),
+            args.get(4).cloned(),
+            config,
+        );
+    }
+
+    if args.get(1).is_some_and(|x| x == "fav") {
+        return commands::fav_command(args.get(2).cloned(), config);
+    }
+
+    if args.get(1).is_some_and(|x| x == "history") {
+        return commands::history_command(args.get(2).cloned(), config);
+    }
+
+    if args.get(1).is_some_and(|x| x == "archive") {
+        return commands::archive_command(args.get(2).cloned(), config);
+    }
+
+    if args.get(1).is_some_and(|x| x == "unarchive") {
+        return commands::unarchive_command(args.get(2).cloned(), config);
+    }
+
+    if args.get(1).is_some_and(|x| x == "prune") {
+        return commands::prune_projects(flag_value(&args, "--older-than", "-O"), config);
+    }
 
     if args.contains(&"--delete".to_string()) || args.contains(&"-d".to_string()) {
         let project = args.get(2).cloned();
@@ -28,26 +82,44 @@ fn main() -> io::Result<()> {
     }
 
     if args.contains(&"--new".to_string()) || args.contains(&"-n".to_string()) {
-        let project = commands::new_project(args.get(2).cloned(), config.clone())?;
-        if let Some((project, project_dir)) = project {
-            let project = repo::Repo::new(project, true, Some(project_dir));
-
-            println!("Project {} created successfully!", project.name());
-
-            return match config.general().open_new_projects() {
-                true => intergrations::tmuxinator::run_tmuxinator(&project, config.tmuxinator()),
-                false => Ok(()),
-            };
+        let project = commands::new_project(
+            args.get(2).cloned(),
+            flag_value(&args, "--template", "-t"),
+            flag_value(&args, "--dir", "-D"),
+            config.clone(),
+        )?;
+        match project {
+            Some(commands::NewProject::Created(project)) => {
+                println!("Project {} created successfully!", project.name());
+
+                return match config.general().open_new_projects() {
+                    true => commands::launch_project(&project, &config),
+                    false => Ok(()),
+                };
+            }
+            Some(commands::NewProject::Existing(project)) => {
+                return commands::launch_project(&project, &config);
+            }
+            None => {}
         }
+        return Ok(());
     }
 
     if args.contains(&"--clone".to_string()) || args.contains(&"-c".to_string()) {
-        let repo = commands::git_clone(args.get(2).cloned(), &config);
+        let repo = commands::git_clone(args.get(2).cloned(), &config)?;
         if let Some(repo) = repo {
-            return intergrations::tmuxinator::run_tmuxinator(&repo, config.tmuxinator());
+            return commands::launch_project(&repo, &config);
         }
     }
 
+    if args.contains(&"--last".to_string()) {
+        return commands::open_last_project(config);
+    }
+
+    if args.get(1).is_some_and(|x| x == "-") {
+        return commands::open_previous_project(config);
+    }
+
     if args.contains(&"--open".to_string()) || args.contains(&"-o".to_string()) {
         let project = args.get(2).cloned();
         if let Some(project_name) = project {
@@ -64,5 +136,23 @@ fn main() -> io::Result<()> {
         return commands::list_projects(project_dir_filter, config);
     }
 
-    commands::open_project(config)
+    let filter = match args.get(1) {
+        Some(tag) if tag.starts_with('#') => ProjectFilter::Tag(tag[1..].to_string()),
+        Some(flag) if flag == "--favourites" || flag == "-f" => ProjectFilter::Favourites,
+        _ => ProjectFilter::All,
+    };
+
+    commands::open_project(filter, config)
+}
+
+/// Gets the value passed after a flag, e.g. `rust` in `--template rust`
+///
+/// # Parameters
+///
+/// - `args`  The arguments passed to the program
+/// - `long`  The long version of the flag
+/// - `short` The short version of the flag
+fn flag_value(args: &[String], long: &str, short: &str) -> Option<String> {
+    let position = args.iter().position(|x| x == long || x == short)?;
+    args.get(position + 1).cloned()
 }
@@ -11,6 +11,8 @@ pub struct Repo {
     local: bool,
     /// The path to the projects directory
     project_dir: Option<String>,
+    /// Whether the project is in the archive rather than checked out
+    archived: bool,
 }
 
 impl PartialEq for Repo {
@@ -40,6 +42,20 @@ impl Repo {
             name,
             local,
             project_dir,
+            archived: false,
+        }
+    }
+
+    /// Creates a repo struct for a project in the archive
+    ///
+    /// # Arguments
+    ///
+    /// - `name`        The name of the repo
+    /// - `project_dir` The path to the directory the project was archived from
+    pub fn new_archived<T: Into<String>>(name: T, project_dir: T) -> Self {
+        Self {
+            archived: true,
+            ..Self::new(name, false, Some(project_dir))
         }
     }
 
@@ -69,6 +85,16 @@ impl Repo {
         self.local
     }
 
+    /// Whether the project is in the archive rather than checked out
+    pub fn archived(&self) -> bool {
+        self.archived
+    }
+
+    /// The projects directory containing the project, e.g. `Projects/`
+    pub fn project_dir(&self) -> Option<String> {
+        self.project_dir.clone()
+    }
+
     /// Gets the root directory for the tmuxinator config
     ///
     /// # Returns
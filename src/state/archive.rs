@@ -0,0 +1,291 @@
+//! This module contains the logic for archived projects
+//!
+//! Each archived project is a `.tar.zst` of its directory, next to a `.toml` file describing
+//! where it came from
+
+use std::{
+    cmp::Reverse,
+    fs, io,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use serde::{Deserialize, Serialize};
+
+/// The extension of archives
+const ARCHIVE_EXTENSION: &str = "tar.zst";
+
+/// The extension of the metadata stored next to an archive
+const META_EXTENSION: &str = "toml";
+
+/// The metadata stored next to an archived project
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+struct ArchiveMeta {
+    /// The name of the project
+    name: String,
+    /// The projects directory the project was in, e.g. `Projects/`
+    project_dir: String,
+    /// Where the project was before it was archived
+    original_path: PathBuf,
+    /// When the project was archived, in seconds since the unix epoch
+    archived_at: u64,
+    /// The directories and files that were left out of the archive
+    excludes: Vec<String>,
+}
+
+/// A project in the archive
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArchivedProject {
+    /// The `.tar.zst` the project is packed in
+    archive: PathBuf,
+    meta: ArchiveMeta,
+}
+
+impl ArchivedProject {
+    /// The name of the project
+    pub fn name(&self) -> &str {
+        self.meta.name.as_ref()
+    }
+
+    /// The projects directory the project was in, e.g. `Projects/`
+    pub fn project_dir(&self) -> &str {
+        self.meta.project_dir.as_ref()
+    }
+
+    /// Where the project was before it was archived
+    pub fn original_path(&self) -> &Path {
+        self.meta.original_path.as_ref()
+    }
+
+    /// When the project was archived
+    pub fn archived_at(&self) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(self.meta.archived_at)
+    }
+
+    /// The `.tar.zst` the project is packed in
+    pub fn archive(&self) -> &Path {
+        self.archive.as_ref()
+    }
+
+    /// The directories and files that were left out of the archive
+    pub fn excludes(&self) -> &[String] {
+        self.meta.excludes.as_ref()
+    }
+
+    /// The file the metadata of the project is stored in
+    fn meta_file(&self) -> PathBuf {
+        // Dropping the extension twice removes both the `.zst` and `.tar`
+        self.archive
+            .with_extension("")
+            .with_extension(META_EXTENSION)
+    }
+}
+
+/// Packs a project into a `.tar.zst` in the archive directory. The project itself is left as is
+///
+/// # Parameters
+///
+/// - `archive_dir`  The archive directory
+/// - `name`         The name of the project
+/// - `project_dir`  The projects directory the project is in, e.g. `Projects/`
+/// - `project_root` The project's directory
+/// - `excludes`     Names of directories and files to leave out, matched at any depth
+pub fn archive_project(
+    archive_dir: &Path,
+    name: &str,
+    project_dir: &str,
+    project_root: &Path,
+    excludes: &[String],
+) -> io::Result<ArchivedProject> {
+    // Collecting the components drops any trailing slash
+    let project_root: PathBuf = project_root.components().collect();
+    let (parent, dir_name) = match (project_root.parent(), project_root.file_name()) {
+        (Some(parent), Some(dir_name)) => (parent, dir_name),
+        _ => {
+            return Err(io::Error::other(format!(
+                "{} can't be archived",
+                project_root.display()
+            )))
+        }
+    };
+
+    fs::create_dir_all(archive_dir)?;
+
+    let archived_at = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+
+    // Archiving a project with the same name twice in a second shouldn't overwrite the first
+    let mut stem = format!("{}-{}", name, archived_at);
+    let mut suffix = 1;
+    while archive_dir
+        .join(format!("{}.{}", stem, ARCHIVE_EXTENSION))
+        .exists()
+    {
+        stem = format!("{}-{}-{}", name, archived_at, suffix);
+        suffix += 1;
+    }
+
+    let archive = archive_dir.join(format!("{}.{}", stem, ARCHIVE_EXTENSION));
+    // Written under another name first, so a failed archive is never mistaken for a finished one
+    let partial = archive_dir.join(format!("{}.{}.partial", stem, ARCHIVE_EXTENSION));
+
+    let status = Command::new("tar")
+        .arg("--zstd")
+        .arg("-cf")
+        .arg(&partial)
+        .args(
+            excludes
+                .iter()
+                .map(|exclude| format!("--exclude={}", exclude)),
+        )
+        .arg("-C")
+        .arg(parent)
+        .arg(dir_name)
+        .stderr(Stdio::inherit())
+        .status();
+
+    if !status.as_ref().is_ok_and(|status| status.success()) {
+        let _ = fs::remove_file(&partial);
+        status?;
+        return Err(io::Error::other("tar couldn't create the archive"));
+    }
+    fs::rename(&partial, &archive)?;
+
+    let project = ArchivedProject {
+        archive,
+        meta: ArchiveMeta {
+            name: name.to_string(),
+            project_dir: project_dir.to_string(),
+            original_path: project_root.clone(),
+            archived_at,
+            excludes: excludes.to_vec(),
+        },
+    };
+
+    let meta = toml::to_string(&project.meta).map_err(io::Error::other)?;
+    fs::write(project.meta_file(), meta)?;
+
+    Ok(project)
+}
+
+/// Gets the projects in the archive, most recently archived first. Archives whose metadata can't
+/// be read are skipped
+///
+/// # Parameters
+///
+/// - `archive_dir` The archive directory
+pub fn list(archive_dir: &Path) -> Vec<ArchivedProject> {
+    let entries = match fs::read_dir(archive_dir) {
+        Ok(entries) => entries,
+        Err(_) => return vec![],
+    };
+
+    let mut projects: Vec<ArchivedProject> = entries
+        .filter_map(|entry| {
+            let meta_file = entry.ok()?.path();
+            if meta_file.extension()? != META_EXTENSION {
+                return None;
+            }
+
+            let meta = fs::read_to_string(&meta_file).ok()?;
+            let meta = toml::from_str(&meta).ok()?;
+            let archive = meta_file.with_extension(ARCHIVE_EXTENSION);
+
+            archive
+                .is_file()
+                .then_some(ArchivedProject { archive, meta })
+        })
+        .collect();
+
+    projects.sort_by_key(|project| Reverse(project.meta.archived_at));
+    projects
+}
+
+/// Unpacks an archived project back to where it was, removing it from the archive
+///
+/// # Parameters
+///
+/// - `project` The project to unarchive
+pub fn unarchive_project(project: &ArchivedProject) -> io::Result<()> {
+    if project.original_path().exists() {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!("{} already exists", project.original_path().display()),
+        ));
+    }
+
+    let parent = project
+        .original_path()
+        .parent()
+        .ok_or_else(|| io::Error::other("the project has no projects directory"))?;
+    fs::create_dir_all(parent)?;
+
+    let status = Command::new("tar")
+        .arg("--zstd")
+        .arg("-xf")
+        .arg(project.archive())
+        .arg("-C")
+        .arg(parent)
+        .status()?;
+
+    if !status.success() {
+        return Err(io::Error::other("tar couldn't unpack the archive"));
+    }
+
+    fs::remove_file(project.meta_file())?;
+    fs::remove_file(project.archive())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use crate::test_utils::TempDir;
+
+    use super::{archive_project, list, unarchive_project};
+
+    #[test]
+    fn archive_and_unarchive_works() {
+        let base = TempDir::new("archive");
+        let archive_dir = base.join("archive");
+        let project_root = base.join("Projects/workflows/");
+
+        fs::create_dir_all(project_root.join("src")).unwrap();
+        fs::create_dir_all(project_root.join("target/debug")).unwrap();
+        fs::write(project_root.join("src/main.rs"), "fn main() {}").unwrap();
+        fs::write(project_root.join("target/debug/workflows"), "").unwrap();
+
+        let excludes = vec!["target".to_string()];
+        let project = archive_project(
+            &archive_dir,
+            "workflows",
+            "Projects/",
+            &project_root,
+            &excludes,
+        )
+        .unwrap();
+
+        assert!(project.archive().is_file());
+        assert_eq!(list(&archive_dir), vec![project.clone()]);
+        assert_eq!(project.name(), "workflows");
+        assert_eq!(project.project_dir(), "Projects/");
+        assert_eq!(project.original_path(), base.join("Projects/workflows"));
+        assert_eq!(project.excludes(), excludes);
+
+        // Unarchiving never overwrites a project
+        assert!(unarchive_project(&project).is_err());
+
+        fs::remove_dir_all(&project_root).unwrap();
+        unarchive_project(&project).unwrap();
+
+        assert_eq!(
+            fs::read_to_string(project_root.join("src/main.rs")).unwrap(),
+            "fn main() {}"
+        );
+        assert!(!project_root.join("target").exists());
+        assert!(list(&archive_dir).is_empty());
+    }
+}
@@ -0,0 +1,240 @@
+//! This module contains the logic for the backups made of unpushed work before a project is
+//! deleted
+//!
+//! Each backup gets its own directory, containing a git bundle of every ref in the project, a
+//! patch of its uncommitted changes and a copy of its precious ignored files, if it had any
+
+use std::{
+    fs, io,
+    os::unix::fs::symlink,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::intergrations::git;
+
+use super::data_dir;
+
+/// The file uncommitted changes are written to, inside a backup
+const PATCH_FILE: &str = "uncommitted.patch";
+
+/// The directory precious files are copied to, inside a backup
+const PRECIOUS_DIR: &str = "precious";
+
+/// A backup of a project's unpushed work
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Backup {
+    /// The directory of the backup
+    dir: PathBuf,
+    /// The bundle of every ref, `None` if the project had no commits
+    bundle: Option<PathBuf>,
+    /// The patch of uncommitted changes, `None` if there weren't any
+    patch: Option<PathBuf>,
+    /// The copies of the precious ignored files, laid out as they were in the project. `None`
+    /// if there weren't any
+    precious: Option<PathBuf>,
+}
+
+impl Backup {
+    /// The directory of the backup
+    pub fn dir(&self) -> &Path {
+        self.dir.as_ref()
+    }
+
+    /// The bundle of every ref, `None` if the project had no commits
+    pub fn bundle(&self) -> Option<&Path> {
+        self.bundle.as_deref()
+    }
+
+    /// The patch of uncommitted changes, `None` if there weren't any
+    pub fn patch(&self) -> Option<&Path> {
+        self.patch.as_deref()
+    }
+
+    /// The copies of the precious ignored files, laid out as they were in the project. `None`
+    /// if there weren't any
+    pub fn precious(&self) -> Option<&Path> {
+        self.precious.as_deref()
+    }
+}
+
+/// The directory backups are written to
+///
+/// # Returns
+///
+/// A [`PathBuf`] leading to ~/.local/share/workflows/backups/ on Linux
+pub fn backups_dir() -> Option<PathBuf> {
+    Some(data_dir()?.join("backups"))
+}
+
+/// Backs up every ref, the uncommitted changes and the precious ignored files of a project
+///
+/// # Parameters
+///
+/// - `backups_dir` The backups directory
+/// - `name` The name of the project
+/// - `project_root` The root of the project's repo
+/// - `precious` The precious ignored files, relative to the project root, e.g. `.env`
+pub fn create_backup(
+    backups_dir: &Path,
+    name: &str,
+    project_root: &Path,
+    precious: &[String],
+) -> io::Result<Backup> {
+    let created_at = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+
+    // Backing up a project with the same name twice in a second shouldn't overwrite the first
+    let mut dir = backups_dir.join(format!("{}-{}", name, created_at));
+    let mut suffix = 1;
+    while dir.exists() {
+        dir = backups_dir.join(format!("{}-{}-{}", name, created_at, suffix));
+        suffix += 1;
+    }
+    fs::create_dir_all(&dir)?;
+
+    let bundle = dir.join(format!("{}.bundle", name));
+    let bundle = git::bundle(project_root, &bundle)?.then_some(bundle);
+
+    let backup = back_up_changes(&dir, project_root, precious);
+
+    let (patch, precious) = match backup {
+        Ok(backup) => backup,
+        Err(err) => {
+            let _ = fs::remove_dir_all(&dir);
+            return Err(err);
+        }
+    };
+
+    if bundle.is_none() && patch.is_none() && precious.is_none() {
+        fs::remove_dir_all(&dir)?;
+        return Err(io::Error::other(
+            "there was nothing that could be backed up",
+        ));
+    }
+
+    Ok(Backup {
+        dir,
+        bundle,
+        patch,
+        precious,
+    })
+}
+
+/// Writes the patch of uncommitted changes and copies the precious files into a backup
+///
+/// # Parameters
+///
+/// - `dir` The directory of the backup
+/// - `project_root` The root of the project's repo
+/// - `precious` The precious ignored files, relative to the project root
+///
+/// # Returns
+///
+/// The patch and the directory of precious files, if there were any
+fn back_up_changes(
+    dir: &Path,
+    project_root: &Path,
+    precious: &[String],
+) -> io::Result<(Option<PathBuf>, Option<PathBuf>)> {
+    let patch = git::uncommitted_patch(project_root)?;
+    let patch = match patch.is_empty() {
+        true => None,
+        false => {
+            let path = dir.join(PATCH_FILE);
+            fs::write(&path, patch)?;
+            Some(path)
+        }
+    };
+
+    let precious_dir = dir.join(PRECIOUS_DIR);
+    for file in precious {
+        let file = file.trim_end_matches('/');
+        copy_path(&project_root.join(file), &precious_dir.join(file))?;
+    }
+    let precious = precious_dir.exists().then_some(precious_dir);
+
+    Ok((patch, precious))
+}
+
+/// Copies a file or directory, keeping symlinks as links rather than following them
+///
+/// # Parameters
+///
+/// - `from` The file or directory to copy
+/// - `to` Where to copy it to
+fn copy_path(from: &Path, to: &Path) -> io::Result<()> {
+    if let Some(parent) = to.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let file_type = fs::symlink_metadata(from)?.file_type();
+
+    if file_type.is_symlink() {
+        return symlink(fs::read_link(from)?, to);
+    }
+
+    if !file_type.is_dir() {
+        return fs::copy(from, to).map(|_| ());
+    }
+
+    fs::create_dir_all(to)?;
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        copy_path(&entry.path(), &to.join(entry.file_name()))?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use crate::{
+        intergrations::git,
+        test_utils::{self, TempDir},
+    };
+
+    use super::create_backup;
+
+    #[test]
+    fn create_backup_works() {
+        let base = TempDir::new("backup");
+        let backups_dir = base.join("backups");
+        let project_root = base.join("workflows");
+
+        test_utils::init_repo(&project_root);
+        fs::write(project_root.join("main.rs"), "fn main() {}\n").unwrap();
+        fs::write(project_root.join(".gitignore"), ".env\n").unwrap();
+        assert!(git::commit_all(&project_root, "Initial commit").unwrap());
+
+        fs::write(project_root.join("main.rs"), "fn main() { todo!() }\n").unwrap();
+        fs::write(project_root.join("notes.md"), "# Notes\n").unwrap();
+        fs::create_dir_all(project_root.join("config")).unwrap();
+        fs::write(project_root.join("config/.env"), "SECRET=1").unwrap();
+
+        let precious = vec!["config/.env".to_string()];
+        let backup = create_backup(&backups_dir, "workflows", &project_root, &precious).unwrap();
+
+        assert!(backup.dir().starts_with(&backups_dir));
+        assert!(backup.bundle().unwrap().is_file());
+
+        let patch = fs::read_to_string(backup.patch().unwrap()).unwrap();
+        assert!(patch.contains("+fn main() { todo!() }"));
+        assert!(patch.contains("+# Notes"));
+        assert!(!patch.contains("SECRET"));
+
+        let precious = backup.precious().unwrap();
+        assert_eq!(
+            fs::read_to_string(precious.join("config/.env")).unwrap(),
+            "SECRET=1"
+        );
+
+        // The repo's own index is left alone, so the new file is still untracked
+        let status = git::status(&project_root).unwrap();
+        assert_eq!(status.untracked(), ["notes.md"]);
+    }
+}
@@ -0,0 +1,393 @@
+//! This module contains the history of opened projects, used to rank projects by frecency: how
+//! often they're opened, weighted by how recently
+
+use std::{
+    cmp::{Ordering, Reverse},
+    io,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::repo::Repo;
+
+use super::{data_dir, load_toml, tags::ProjectId};
+
+/// The number of seconds in an hour
+const SECONDS_IN_HOUR: u64 = 60 * 60;
+
+/// The number of seconds in a day
+const SECONDS_IN_DAY: u64 = SECONDS_IN_HOUR * 24;
+
+/// The number of seconds in a week
+const SECONDS_IN_WEEK: u64 = SECONDS_IN_DAY * 7;
+
+/// How often, and how recently, a project has been opened
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct HistoryEntry {
+    /// The name of the project
+    name: String,
+    /// The projects directory the project is in, e.g. `Projects/`
+    project_dir: String,
+    /// How many times the project has been opened
+    opens: u64,
+    /// When the project was last opened, in seconds since the unix epoch
+    last_opened: u64,
+}
+
+impl HistoryEntry {
+    /// The name of the project
+    pub fn name(&self) -> &str {
+        self.name.as_ref()
+    }
+
+    /// The projects directory the project is in, e.g. `Projects/`
+    pub fn project_dir(&self) -> &str {
+        self.project_dir.as_ref()
+    }
+
+    /// How many times the project has been opened
+    pub fn opens(&self) -> u64 {
+        self.opens
+    }
+
+    /// When the project was last opened
+    pub fn last_opened(&self) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(self.last_opened)
+    }
+
+    /// How often the project is opened, weighted by how recently it was last opened
+    ///
+    /// # Parameters
+    ///
+    /// - `now` The time to weigh the last open against
+    pub fn frecency(&self, now: SystemTime) -> f64 {
+        let age = now
+            .duration_since(self.last_opened())
+            .unwrap_or_default()
+            .as_secs();
+
+        let weight = match age {
+            age if age < SECONDS_IN_HOUR => 4.0,
+            age if age < SECONDS_IN_DAY => 2.0,
+            age if age < SECONDS_IN_WEEK => 0.5,
+            _ => 0.25,
+        };
+
+        self.opens as f64 * weight
+    }
+
+    /// Whether this entry is for the given project
+    ///
+    /// # Parameters
+    ///
+    /// - `repo` The project to compare against
+    fn is(&self, repo: &Repo) -> bool {
+        self.name == repo.name() && self.project_dir == repo.project_dir().unwrap_or_default()
+    }
+}
+
+/// The history of opened projects
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct History {
+    #[serde(default)]
+    projects: Vec<HistoryEntry>,
+}
+
+impl History {
+    /// Reads the history from a file. A missing file is an empty history
+    ///
+    /// # Parameters
+    ///
+    /// - `file` The history file
+    ///
+    /// # Returns
+    ///
+    /// The history, or an error if the file couldn't be read or parsed
+    pub fn load(file: &Path) -> io::Result<Self> {
+        load_toml(file)
+    }
+
+    /// The projects that have been opened, most recently opened first
+    pub fn entries(&self) -> Vec<&HistoryEntry> {
+        let mut entries: Vec<&HistoryEntry> = self.projects.iter().collect();
+        entries.sort_by_key(|entry| Reverse(entry.last_opened));
+        entries
+    }
+
+    /// Removes a project from the history, for when it's gone for good
+    ///
+    /// # Parameters
+    ///
+    /// - `repo` The project to remove
+    ///
+    /// # Returns
+    ///
+    /// Whether the project was in the history
+    pub fn forget(&mut self, repo: &Repo) -> bool {
+        let count = self.projects.len();
+        self.projects.retain(|entry| !entry.is(repo));
+        self.projects.len() != count
+    }
+
+    /// Drops the projects that aren't in the given list, such as projects removed without
+    /// workflows
+    ///
+    /// # Parameters
+    ///
+    /// - `projects` The projects that exist
+    pub fn retain_existing(mut self, projects: &[Repo]) -> Self {
+        self.projects
+            .retain(|entry| projects.iter().any(|project| entry.is(project)));
+        self
+    }
+
+    /// Records that a project was opened
+    ///
+    /// # Parameters
+    ///
+    /// - `repo` The project that was opened
+    /// - `now`  When it was opened
+    pub fn record(&mut self, repo: &Repo, now: SystemTime) {
+        let last_opened = now.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+
+        match self.projects.iter_mut().find(|entry| entry.is(repo)) {
+            Some(entry) => {
+                entry.opens += 1;
+                entry.last_opened = last_opened;
+            }
+            None => self.projects.push(HistoryEntry {
+                name: repo.name(),
+                project_dir: repo.project_dir().unwrap_or_default(),
+                opens: 1,
+                last_opened,
+            }),
+        }
+    }
+
+    /// Orders projects for the picker. Pinned projects come first in the order they're pinned,
+    /// then favourites in the order they were added, followed by the rest by frecency. Projects
+    /// that have never been opened are sorted by name
+    ///
+    /// # Parameters
+    ///
+    /// - `projects`   The projects to order
+    /// - `pinned`     The projects the user has pinned to the top. See [`is_pinned`]
+    /// - `favourites` The user's favourite projects
+    /// - `now`        The time to weigh the last opens against
+    pub fn rank(
+        &self,
+        projects: &mut [Repo],
+        pinned: &[String],
+        favourites: &[ProjectId],
+        now: SystemTime,
+    ) {
+        let pin = |repo: &Repo| {
+            pinned
+                .iter()
+                .position(|pin| is_pinned(pin, repo))
+                .or_else(|| {
+                    favourites
+                        .iter()
+                        .position(|favourite| favourite.is(repo))
+                        .map(|position| pinned.len() + position)
+                })
+                .unwrap_or(pinned.len() + favourites.len())
+        };
+        let frecency = |repo: &Repo| {
+            self.projects
+                .iter()
+                .find(|entry| entry.is(repo))
+                .map_or(0.0, |entry| entry.frecency(now))
+        };
+
+        projects.sort_by(|a, b| {
+            pin(a)
+                .cmp(&pin(b))
+                .then(
+                    frecency(b)
+                        .partial_cmp(&frecency(a))
+                        .unwrap_or(Ordering::Equal),
+                )
+                .then(a.name().cmp(&b.name()))
+        });
+    }
+}
+
+/// Whether a pin matches a project. A pin is either a project's name, matching it in every
+/// projects directory, or its projects directory and name, e.g. `Work/api`
+///
+/// # Parameters
+///
+/// - `pin`  The pin, from the user's config
+/// - `repo` The project to check
+fn is_pinned(pin: &str, repo: &Repo) -> bool {
+    match pin.rsplit_once('/') {
+        Some((project_dir, name)) => {
+            name == repo.name()
+                && project_dir.trim_end_matches('/')
+                    == repo.project_dir().unwrap_or_default().trim_end_matches('/')
+        }
+        None => pin == repo.name(),
+    }
+}
+
+/// The file the history of opened projects is stored in
+///
+/// # Returns
+///
+/// A [`PathBuf`] leading to ~/.local/share/workflows/history.toml on Linux
+pub fn history_file() -> Option<PathBuf> {
+    Some(data_dir()?.join("history.toml"))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, SystemTime};
+
+    use crate::{
+        repo::Repo,
+        state::{save_toml, tags::ProjectId},
+        test_utils::TempDir,
+    };
+
+    use super::{is_pinned, History, SECONDS_IN_DAY};
+
+    #[test]
+    fn record_works() {
+        let mut history = History::default();
+        let now = SystemTime::now();
+        let repo = Repo::new("workflows", true, Some("Projects/"));
+
+        history.record(&repo, now - Duration::from_secs(60));
+        history.record(&repo, now);
+        // The same name in another projects directory is another project
+        history.record(&Repo::new("workflows", true, Some("Work/")), now);
+
+        let entries = history.entries();
+        assert_eq!(entries.len(), 2);
+
+        let entry = entries
+            .iter()
+            .find(|entry| entry.project_dir() == "Projects/")
+            .unwrap();
+        assert_eq!(entry.name(), "workflows");
+        assert_eq!(entry.opens(), 2);
+        assert_eq!(entry.frecency(now), 8.0);
+        assert_eq!(
+            entry.frecency(now + Duration::from_secs(30 * SECONDS_IN_DAY)),
+            0.5
+        );
+    }
+
+    #[test]
+    fn rank_works() {
+        let mut history = History::default();
+        let now = SystemTime::now();
+        let month_ago = now - Duration::from_secs(30 * SECONDS_IN_DAY);
+
+        let daily = Repo::new("daily", true, Some("Projects/"));
+        let old = Repo::new("old", true, Some("Projects/"));
+        for _ in 0..5 {
+            history.record(&old, month_ago);
+        }
+        history.record(&daily, now);
+
+        let favourite = Repo::new("favourite", true, Some("Work/"));
+
+        let mut projects = vec![
+            Repo::new("unopened-b", true, Some("Projects/")),
+            old.clone(),
+            Repo::new("favourite", true, Some("Projects/")),
+            Repo::new("pinned", true, Some("Projects/")),
+            favourite.clone(),
+            Repo::new("unopened-a", true, Some("Projects/")),
+            daily.clone(),
+        ];
+        history.rank(
+            &mut projects,
+            &["pinned".to_string()],
+            &[ProjectId::of(&favourite)],
+            now,
+        );
+
+        let names: Vec<String> = projects
+            .iter()
+            .map(|x| format!("{}{}", x.project_dir().unwrap(), x.name()))
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                "Projects/pinned",
+                "Work/favourite",
+                "Projects/daily",
+                "Projects/old",
+                "Projects/favourite",
+                "Projects/unopened-a",
+                "Projects/unopened-b"
+            ]
+        );
+    }
+
+    #[test]
+    fn pins_match_by_name_or_projects_dir() {
+        let personal = Repo::new("api", true, Some("Projects/"));
+        let work = Repo::new("api", true, Some("Work/"));
+
+        assert!(is_pinned("api", &personal));
+        assert!(is_pinned("api", &work));
+        assert!(is_pinned("Work/api", &work));
+        assert!(is_pinned("Work//api", &work));
+        assert!(!is_pinned("Work/api", &personal));
+        assert!(!is_pinned("Work/", &work));
+
+        let mut projects = vec![personal.clone(), work.clone()];
+        History::default().rank(
+            &mut projects,
+            &["Work/api".to_string()],
+            &[],
+            SystemTime::now(),
+        );
+        // Repos compare by name, so the projects directory is checked instead
+        assert_eq!(projects[0].project_dir(), work.project_dir());
+    }
+
+    #[test]
+    fn forget_and_retain_existing_work() {
+        let mut history = History::default();
+        let now = SystemTime::now();
+        let kept = Repo::new("workflows", true, Some("Projects/"));
+        let deleted = Repo::new("workflows", true, Some("Work/"));
+        let removed = Repo::new("dotfiles", true, Some("Projects/"));
+
+        for repo in [&kept, &deleted, &removed] {
+            history.record(repo, now);
+        }
+
+        assert!(history.forget(&deleted));
+        assert!(!history.forget(&deleted));
+
+        let history = history.retain_existing(&[kept.clone(), deleted]);
+        let entries = history.entries();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].project_dir(), "Projects/");
+    }
+
+    #[test]
+    fn save_and_load_works() {
+        let dir = TempDir::new("history");
+        let file = dir.join("history.toml");
+
+        assert_eq!(History::load(&file).unwrap(), History::default());
+
+        let mut history = History::default();
+        history.record(
+            &Repo::new("workflows", true, Some("Projects/")),
+            SystemTime::now(),
+        );
+        save_toml(&file, &history).unwrap();
+
+        assert_eq!(History::load(&file).unwrap(), history);
+    }
+}
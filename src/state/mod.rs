@@ -0,0 +1,252 @@
+//! This module contains the state workflows keeps between runs, such as the trash, backups,
+//! archived projects, the history of opened projects and tags
+
+use std::{
+    ffi::OsString,
+    fs::{self, OpenOptions},
+    io,
+    path::{Path, PathBuf},
+    thread,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use serde::{de::DeserializeOwned, Serialize};
+
+pub mod archive;
+pub mod backups;
+pub mod history;
+pub mod tags;
+pub mod trash;
+
+/// The directory workflows stores its state in
+///
+/// # Returns
+///
+/// A [`PathBuf`] leading to ~/.local/share/workflows/ on Linux, or `None` if there is no data
+/// directory on the platform
+pub fn data_dir() -> Option<PathBuf> {
+    Some(dirs::data_dir()?.join("workflows"))
+}
+
+/// How long to wait for another workflows process to finish changing a state file
+const LOCK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// How old a lock has to be before it's assumed to have been left behind by a process that was
+/// killed
+const STALE_LOCK_AGE: Duration = Duration::from_secs(30);
+
+/// Reads a toml state file. A missing file is the default state
+///
+/// # Parameters
+///
+/// - `file` The state file
+///
+/// # Returns
+///
+/// The state, or an error if the file couldn't be read or parsed. Parse errors are
+/// [`io::ErrorKind::InvalidData`]
+pub fn load_toml<T: DeserializeOwned + Default>(file: &Path) -> io::Result<T> {
+    let contents = match fs::read_to_string(file) {
+        Ok(contents) => contents,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(T::default()),
+        Err(err) => return Err(err),
+    };
+
+    toml::from_str(&contents).map_err(|err| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("{} couldn't be parsed: {}", file.display(), err.message()),
+        )
+    })
+}
+
+/// Reads a toml state file so it can be changed. A file that can't be parsed is moved aside, so
+/// saving the change doesn't overwrite it, and the default state is used instead
+///
+/// # Parameters
+///
+/// - `file` The state file
+///
+/// # Returns
+///
+/// The state, along with where the file was moved to if it couldn't be parsed
+pub fn load_toml_for_update<T: DeserializeOwned + Default>(
+    file: &Path,
+) -> io::Result<(T, Option<PathBuf>)> {
+    match load_toml(file) {
+        Ok(state) => Ok((state, None)),
+        Err(err) if err.kind() == io::ErrorKind::InvalidData => {
+            let now = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs();
+            let backup = with_suffix(file, &format!(".{}.bak", now));
+
+            fs::rename(file, &backup)?;
+            Ok((T::default(), Some(backup)))
+        }
+        Err(err) => Err(err),
+    }
+}
+
+/// Writes a toml state file, replacing it in one go so it's never read half written
+///
+/// # Parameters
+///
+/// - `file`  The state file
+/// - `state` The state to write
+pub fn save_toml<T: Serialize>(file: &Path, state: &T) -> io::Result<()> {
+    if let Some(parent) = file.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let contents = toml::to_string(state).map_err(io::Error::other)?;
+    let partial = with_suffix(file, ".partial");
+    fs::write(&partial, contents)?;
+    fs::rename(partial, file)
+}
+
+/// Loads a toml state file, lets it be changed, then saves it. The file is locked throughout, so
+/// a change made by another workflows process at the same time isn't lost. See
+/// [`load_toml_for_update`] for files that can't be parsed
+///
+/// # Parameters
+///
+/// - `file`   The state file
+/// - `update` The change to make
+///
+/// # Returns
+///
+/// Where the file was moved to if it couldn't be parsed
+pub fn update_toml<T: Serialize + DeserializeOwned + Default>(
+    file: &Path,
+    update: impl FnOnce(&mut T),
+) -> io::Result<Option<PathBuf>> {
+    let _lock = StateLock::acquire(file)?;
+
+    let (mut state, backup) = load_toml_for_update(file)?;
+    update(&mut state);
+    save_toml(file, &state)?;
+
+    Ok(backup)
+}
+
+/// Appends a suffix to a path, e.g. `history.toml` to `history.toml.lock`
+///
+/// # Parameters
+///
+/// - `path`   The path to add the suffix to
+/// - `suffix` The suffix to add
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut path = OsString::from(path);
+    path.push(suffix);
+    PathBuf::from(path)
+}
+
+/// A lock on a state file, so only one workflows process changes it at a time. The lock is a
+/// `.lock` file next to the state file, which is removed when dropped
+#[derive(Debug)]
+pub struct StateLock(PathBuf);
+
+impl StateLock {
+    /// Locks a state file, waiting for another process to unlock it if it's already locked
+    ///
+    /// # Parameters
+    ///
+    /// - `file` The state file to lock
+    ///
+    /// # Returns
+    ///
+    /// The lock, or an error if the file stayed locked for too long
+    pub fn acquire(file: &Path) -> io::Result<Self> {
+        let lock = with_suffix(file, ".lock");
+        if let Some(parent) = lock.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let start = Instant::now();
+        loop {
+            match OpenOptions::new().write(true).create_new(true).open(&lock) {
+                Ok(_) => return Ok(Self(lock)),
+                Err(err) if err.kind() == io::ErrorKind::AlreadyExists => {}
+                Err(err) => return Err(err),
+            }
+
+            let age = fs::metadata(&lock)
+                .and_then(|metadata| metadata.modified())
+                .ok()
+                .and_then(|modified| modified.elapsed().ok());
+            if age.is_some_and(|age| age > STALE_LOCK_AGE) {
+                let _ = fs::remove_file(&lock);
+                continue;
+            }
+
+            if start.elapsed() > LOCK_TIMEOUT {
+                return Err(io::Error::new(
+                    io::ErrorKind::WouldBlock,
+                    format!("{} is locked by another workflows process", file.display()),
+                ));
+            }
+
+            thread::sleep(Duration::from_millis(20));
+        }
+    }
+}
+
+impl Drop for StateLock {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, thread};
+
+    use crate::test_utils::TempDir;
+
+    use super::{history::History, load_toml, load_toml_for_update, StateLock};
+
+    #[test]
+    fn load_toml_for_update_moves_unparseable_files() {
+        let dir = TempDir::new("state");
+        let file = dir.join("history.toml");
+
+        let (history, backup) = load_toml_for_update::<History>(&file).unwrap();
+        assert_eq!(history, History::default());
+        assert_eq!(backup, None);
+
+        fs::write(&file, "projects = [").unwrap();
+        assert!(load_toml::<History>(&file).is_err());
+
+        let (_, backup) = load_toml_for_update::<History>(&file).unwrap();
+        let backup = backup.unwrap();
+        assert!(!file.exists());
+        assert_eq!(fs::read_to_string(backup).unwrap(), "projects = [");
+    }
+
+    #[test]
+    fn state_lock_works() {
+        let dir = TempDir::new("state-lock");
+        let file = dir.join("count");
+        fs::write(&file, "0").unwrap();
+
+        let threads: Vec<_> = (0..8)
+            .map(|_| {
+                let file = file.clone();
+                thread::spawn(move || {
+                    let _lock = StateLock::acquire(&file).unwrap();
+                    let count: u32 = fs::read_to_string(&file).unwrap().parse().unwrap();
+                    thread::yield_now();
+                    fs::write(&file, (count + 1).to_string()).unwrap();
+                })
+            })
+            .collect();
+        threads
+            .into_iter()
+            .for_each(|thread| thread.join().unwrap());
+
+        assert_eq!(fs::read_to_string(&file).unwrap(), "8");
+        assert!(!dir.join("count.lock").exists());
+    }
+}
@@ -0,0 +1,398 @@
+//! This module contains the tags and favourites the user has given their projects. They're kept
+//! here, rather than in the projects, so they work for any repo
+
+use std::{
+    collections::BTreeMap,
+    io,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::repo::Repo;
+
+use super::{data_dir, load_toml};
+
+/// A project, by its name and the projects directory it's in. Projects with the same name in
+/// different projects directories are different projects
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ProjectId {
+    /// The name of the project
+    name: String,
+    /// The projects directory the project is in, e.g. `Projects/`
+    project_dir: String,
+}
+
+impl ProjectId {
+    /// The id of a project
+    ///
+    /// # Parameters
+    ///
+    /// - `repo` The project
+    pub fn of(repo: &Repo) -> Self {
+        Self {
+            name: repo.name(),
+            project_dir: repo.project_dir().unwrap_or_default(),
+        }
+    }
+
+    /// The name of the project
+    pub fn name(&self) -> &str {
+        self.name.as_ref()
+    }
+
+    /// The projects directory the project is in, e.g. `Projects/`
+    pub fn project_dir(&self) -> &str {
+        self.project_dir.as_ref()
+    }
+
+    /// Whether this is the id of the given project
+    ///
+    /// # Parameters
+    ///
+    /// - `repo` The project to compare against
+    pub fn is(&self, repo: &Repo) -> bool {
+        self.name == repo.name() && self.project_dir == repo.project_dir().unwrap_or_default()
+    }
+}
+
+/// The tags given to a project
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+struct TaggedProject {
+    #[serde(flatten)]
+    project: ProjectId,
+    tags: Vec<String>,
+}
+
+/// The tags and favourites of the user's projects
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Tags {
+    /// The user's favourite projects, in the order they were added
+    #[serde(default)]
+    favourites: Vec<ProjectId>,
+    /// The tags of each project
+    #[serde(default)]
+    tagged: Vec<TaggedProject>,
+}
+
+impl Tags {
+    /// Reads the tags from a file. A missing file has no tags
+    ///
+    /// # Parameters
+    ///
+    /// - `file` The tags file
+    ///
+    /// # Returns
+    ///
+    /// The tags, or an error if the file couldn't be read or parsed
+    pub fn load(file: &Path) -> io::Result<Self> {
+        load_toml(file)
+    }
+
+    /// The user's favourite projects, in the order they were added
+    pub fn favourites(&self) -> &[ProjectId] {
+        self.favourites.as_ref()
+    }
+
+    /// Whether a project is one of the user's favourites
+    ///
+    /// # Parameters
+    ///
+    /// - `project` The project
+    pub fn is_favourite(&self, project: &Repo) -> bool {
+        self.favourites
+            .iter()
+            .any(|favourite| favourite.is(project))
+    }
+
+    /// Adds a project to the user's favourites, or removes it if it already is one
+    ///
+    /// # Parameters
+    ///
+    /// - `project` The project
+    ///
+    /// # Returns
+    ///
+    /// Whether the project is now a favourite
+    pub fn toggle_favourite(&mut self, project: &Repo) -> bool {
+        match self.is_favourite(project) {
+            true => {
+                self.favourites.retain(|favourite| !favourite.is(project));
+                false
+            }
+            false => {
+                self.favourites.push(ProjectId::of(project));
+                true
+            }
+        }
+    }
+
+    /// The tags of a project
+    ///
+    /// # Parameters
+    ///
+    /// - `project` The project
+    pub fn tags_of(&self, project: &Repo) -> &[String] {
+        self.tagged
+            .iter()
+            .find(|tagged| tagged.project.is(project))
+            .map_or(&[], |tagged| tagged.tags.as_ref())
+    }
+
+    /// Whether a project has a tag
+    ///
+    /// # Parameters
+    ///
+    /// - `project` The project
+    /// - `tag`     The tag, without its `#`
+    pub fn has_tag(&self, project: &Repo, tag: &str) -> bool {
+        self.tags_of(project).iter().any(|x| x == tag)
+    }
+
+    /// Every tag in use, alphabetically, with the projects that have it
+    pub fn by_tag(&self) -> BTreeMap<&str, Vec<&ProjectId>> {
+        let mut by_tag: BTreeMap<&str, Vec<&ProjectId>> = BTreeMap::new();
+
+        for tagged in &self.tagged {
+            for tag in &tagged.tags {
+                by_tag.entry(tag).or_default().push(&tagged.project);
+            }
+        }
+
+        by_tag.values_mut().for_each(|projects| projects.sort());
+        by_tag
+    }
+
+    /// Tags a project
+    ///
+    /// # Parameters
+    ///
+    /// - `project` The project
+    /// - `tag`     The tag, without its `#`
+    ///
+    /// # Returns
+    ///
+    /// Whether the tag was added, which it isn't if the project already had it
+    pub fn add_tag(&mut self, project: &Repo, tag: &str) -> bool {
+        if self.has_tag(project, tag) {
+            return false;
+        }
+
+        let index = match self.tagged.iter().position(|x| x.project.is(project)) {
+            Some(index) => index,
+            None => {
+                self.tagged.push(TaggedProject {
+                    project: ProjectId::of(project),
+                    tags: vec![],
+                });
+                self.tagged.len() - 1
+            }
+        };
+
+        let tags = &mut self.tagged[index].tags;
+        tags.push(tag.to_string());
+        tags.sort();
+        true
+    }
+
+    /// Removes a tag from a project
+    ///
+    /// # Parameters
+    ///
+    /// - `project` The project
+    /// - `tag`     The tag, without its `#`
+    ///
+    /// # Returns
+    ///
+    /// Whether the tag was removed, which it isn't if the project didn't have it
+    pub fn remove_tag(&mut self, project: &Repo, tag: &str) -> bool {
+        let tagged = match self.tagged.iter_mut().find(|x| x.project.is(project)) {
+            Some(tagged) => tagged,
+            None => return false,
+        };
+
+        let count = tagged.tags.len();
+        tagged.tags.retain(|x| x != tag);
+        let removed = tagged.tags.len() != count;
+
+        self.tagged.retain(|tagged| !tagged.tags.is_empty());
+
+        removed
+    }
+
+    /// Removes a project's tags, and removes it from the user's favourites
+    ///
+    /// # Parameters
+    ///
+    /// - `project` The project
+    ///
+    /// # Returns
+    ///
+    /// Whether the project had any tags or was a favourite
+    pub fn forget(&mut self, project: &Repo) -> bool {
+        let count = self.favourites.len() + self.tagged.len();
+
+        self.favourites.retain(|favourite| !favourite.is(project));
+        self.tagged.retain(|tagged| !tagged.project.is(project));
+
+        self.favourites.len() + self.tagged.len() != count
+    }
+
+    /// Drops the tags and favourites of projects that aren't in the given list, such as
+    /// projects removed without workflows
+    ///
+    /// # Parameters
+    ///
+    /// - `projects` The projects that exist
+    pub fn retain_existing(mut self, projects: &[Repo]) -> Self {
+        let exists = |id: &ProjectId| projects.iter().any(|project| id.is(project));
+
+        self.favourites.retain(exists);
+        self.tagged.retain(|tagged| exists(&tagged.project));
+        self
+    }
+}
+
+/// The file the tags and favourites of the user's projects are stored in
+///
+/// # Returns
+///
+/// A [`PathBuf`] leading to ~/.local/share/workflows/tags.toml on Linux
+pub fn tags_file() -> Option<PathBuf> {
+    Some(data_dir()?.join("tags.toml"))
+}
+
+/// Loads the user's tags, which are empty if there's no data directory
+///
+/// # Returns
+///
+/// The tags, or an error if the tags file couldn't be read or parsed
+pub fn load_tags() -> io::Result<Tags> {
+    match tags_file() {
+        Some(tags_file) => Tags::load(&tags_file),
+        None => Ok(Tags::default()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{repo::Repo, state::save_toml, test_utils::TempDir};
+
+    use super::Tags;
+
+    #[test]
+    fn tags_work() {
+        let mut tags = Tags::default();
+        let workflows = Repo::new("workflows", true, Some("Projects/"));
+        let website = Repo::new("website", true, Some("Work/"));
+
+        assert!(tags.add_tag(&workflows, "internal"));
+        assert!(tags.add_tag(&workflows, "cli"));
+        assert!(!tags.add_tag(&workflows, "cli"));
+        assert!(tags.add_tag(&website, "client"));
+
+        assert_eq!(tags.tags_of(&workflows), ["cli", "internal"]);
+        assert!(tags.has_tag(&website, "client"));
+        assert!(tags
+            .tags_of(&Repo::new("dotfiles", true, Some("Projects/")))
+            .is_empty());
+
+        let by_tag = tags.by_tag();
+        assert_eq!(
+            by_tag.keys().copied().collect::<Vec<_>>(),
+            ["cli", "client", "internal"]
+        );
+        assert_eq!(by_tag["client"].len(), 1);
+        assert!(by_tag["client"][0].is(&website));
+
+        assert!(tags.remove_tag(&website, "client"));
+        assert!(!tags.remove_tag(&website, "client"));
+        assert!(!tags.by_tag().contains_key("client"));
+    }
+
+    #[test]
+    fn tags_are_kept_per_projects_dir() {
+        let mut tags = Tags::default();
+        let personal = Repo::new("api", true, Some("Projects/"));
+        let work = Repo::new("api", true, Some("Work/"));
+
+        tags.add_tag(&work, "client");
+        tags.toggle_favourite(&work);
+
+        assert!(tags.has_tag(&work, "client"));
+        assert!(!tags.has_tag(&personal, "client"));
+        assert!(tags.is_favourite(&work));
+        assert!(!tags.is_favourite(&personal));
+    }
+
+    #[test]
+    fn favourites_work() {
+        let mut tags = Tags::default();
+        let workflows = Repo::new("workflows", true, Some("Projects/"));
+        let dotfiles = Repo::new("dotfiles", true, Some("Projects/"));
+
+        assert!(tags.toggle_favourite(&workflows));
+        assert!(tags.toggle_favourite(&dotfiles));
+        assert_eq!(
+            tags.favourites()
+                .iter()
+                .map(|favourite| favourite.name())
+                .collect::<Vec<_>>(),
+            ["workflows", "dotfiles"]
+        );
+
+        assert!(!tags.toggle_favourite(&workflows));
+        assert!(!tags.is_favourite(&workflows));
+        assert_eq!(tags.favourites().len(), 1);
+    }
+
+    #[test]
+    fn forget_works() {
+        let mut tags = Tags::default();
+        let workflows = Repo::new("workflows", true, Some("Projects/"));
+        let dotfiles = Repo::new("dotfiles", true, Some("Projects/"));
+
+        tags.add_tag(&workflows, "cli");
+        tags.toggle_favourite(&workflows);
+        tags.add_tag(&dotfiles, "cli");
+
+        assert!(tags.forget(&workflows));
+        assert!(!tags.forget(&workflows));
+        assert!(!tags.is_favourite(&workflows));
+        assert!(tags.tags_of(&workflows).is_empty());
+        assert_eq!(tags.by_tag()["cli"].len(), 1);
+    }
+
+    #[test]
+    fn retain_existing_works() {
+        let mut tags = Tags::default();
+        let workflows = Repo::new("workflows", true, Some("Projects/"));
+        let removed = Repo::new("removed", true, Some("Projects/"));
+
+        tags.add_tag(&workflows, "cli");
+        tags.add_tag(&removed, "cli");
+        tags.toggle_favourite(&removed);
+
+        let tags = tags.retain_existing(std::slice::from_ref(&workflows));
+
+        assert!(tags.favourites().is_empty());
+        assert_eq!(tags.by_tag()["cli"].len(), 1);
+        assert!(tags.by_tag()["cli"][0].is(&workflows));
+    }
+
+    #[test]
+    fn save_and_load_works() {
+        let dir = TempDir::new("tags");
+        let file = dir.join("tags.toml");
+
+        assert_eq!(Tags::load(&file).unwrap(), Tags::default());
+
+        let mut tags = Tags::default();
+        let workflows = Repo::new("workflows", true, Some("Projects/"));
+        tags.add_tag(&workflows, "cli");
+        tags.toggle_favourite(&workflows);
+        save_toml(&file, &tags).unwrap();
+
+        assert_eq!(Tags::load(&file).unwrap(), tags);
+    }
+}
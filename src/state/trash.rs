@@ -0,0 +1,350 @@
+//! This module contains the logic for the trash deleted projects are moved to
+//!
+//! Each trashed project gets its own directory in the trash, containing the project, its
+//! tmuxinator config if it had one, and a `meta.toml` describing where it came from
+
+use std::{
+    cmp::Reverse,
+    fs, io,
+    path::{Path, PathBuf},
+    process::Command,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::repo::Repo;
+
+use super::data_dir;
+
+/// The file the metadata of a trashed project is stored in
+const META_FILE: &str = "meta.toml";
+
+/// The directory a trashed project is moved to, inside its trash entry
+const PROJECT_DIR: &str = "project";
+
+/// The file a trashed project's tmuxinator config is moved to, inside its trash entry
+const TMUXINATOR_FILE: &str = "tmuxinator.yml";
+
+/// The number of seconds in a day
+const SECONDS_IN_DAY: u64 = 60 * 60 * 24;
+
+/// The metadata stored alongside a trashed project
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+struct TrashMeta {
+    /// The name of the project
+    name: String,
+    /// Where the project was before it was trashed
+    original_path: PathBuf,
+    /// The projects directory the project was in, e.g. `Projects/`. Missing for projects trashed
+    /// before it was recorded
+    #[serde(default)]
+    project_dir: Option<String>,
+    /// When the project was trashed, in seconds since the unix epoch
+    deleted_at: u64,
+}
+
+/// A project in the trash
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrashEntry {
+    /// The directory of the entry in the trash
+    dir: PathBuf,
+    meta: TrashMeta,
+}
+
+impl TrashEntry {
+    /// The name of the project
+    pub fn name(&self) -> &str {
+        self.meta.name.as_ref()
+    }
+
+    /// Where the project was before it was trashed
+    pub fn original_path(&self) -> &Path {
+        self.meta.original_path.as_ref()
+    }
+
+    /// The project as it was before it was trashed, `None` if its projects directory wasn't
+    /// recorded
+    pub fn project(&self) -> Option<Repo> {
+        let project_dir = self.meta.project_dir.clone()?;
+
+        Some(Repo::new(self.meta.name.clone(), true, Some(project_dir)))
+    }
+
+    /// When the project was trashed
+    pub fn deleted_at(&self) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(self.meta.deleted_at)
+    }
+
+    /// Whether the project has been in the trash for longer than the retention period
+    ///
+    /// # Parameters
+    ///
+    /// - `retention_days` How many days projects are kept in the trash for
+    pub fn expired(&self, retention_days: u64) -> bool {
+        self.deleted_at()
+            .elapsed()
+            .is_ok_and(|age| age.as_secs() > retention_days.saturating_mul(SECONDS_IN_DAY))
+    }
+}
+
+/// The directory trashed projects are moved to
+///
+/// # Returns
+///
+/// A [`PathBuf`] leading to ~/.local/share/workflows/trash/ on Linux
+pub fn trash_dir() -> Option<PathBuf> {
+    Some(data_dir()?.join("trash"))
+}
+
+/// Moves a project and its tmuxinator config into the trash
+///
+/// # Parameters
+///
+/// - `trash_dir` The trash directory
+/// - `project` The project
+/// - `project_root` The project's directory
+/// - `tmuxinator_file` The project's tmuxinator config, which is only moved if it exists
+pub fn move_to_trash(
+    trash_dir: &Path,
+    project: &Repo,
+    project_root: &Path,
+    tmuxinator_file: &Path,
+) -> io::Result<TrashEntry> {
+    let deleted_at = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let name = project.name();
+
+    // Deleting a project with the same name twice in a second shouldn't overwrite the first
+    let mut dir = trash_dir.join(format!("{}-{}", name, deleted_at));
+    let mut suffix = 1;
+    while dir.exists() {
+        dir = trash_dir.join(format!("{}-{}-{}", name, deleted_at, suffix));
+        suffix += 1;
+    }
+    fs::create_dir_all(&dir)?;
+
+    let entry = TrashEntry {
+        dir,
+        meta: TrashMeta {
+            name: name.clone(),
+            // Collecting the components drops any trailing slash
+            original_path: project_root.components().collect(),
+            project_dir: project.project_dir(),
+            deleted_at,
+        },
+    };
+
+    let meta = toml::to_string(&entry.meta).map_err(io::Error::other)?;
+    fs::write(entry.dir.join(META_FILE), meta)?;
+
+    if let Err(err) = move_path(project_root, &entry.dir.join(PROJECT_DIR)) {
+        fs::remove_dir_all(&entry.dir)?;
+        return Err(err);
+    }
+
+    if tmuxinator_file.is_file() {
+        move_path(tmuxinator_file, &entry.dir.join(TMUXINATOR_FILE))?;
+    }
+
+    Ok(entry)
+}
+
+/// Gets the projects in the trash, most recently trashed first. Entries that can't be read are
+/// skipped
+///
+/// # Parameters
+///
+/// - `trash_dir` The trash directory
+pub fn list(trash_dir: &Path) -> Vec<TrashEntry> {
+    let entries = match fs::read_dir(trash_dir) {
+        Ok(entries) => entries,
+        Err(_) => return vec![],
+    };
+
+    let mut entries: Vec<TrashEntry> = entries
+        .filter_map(|entry| {
+            let dir = entry.ok()?.path();
+            let meta = fs::read_to_string(dir.join(META_FILE)).ok()?;
+            let meta = toml::from_str(&meta).ok()?;
+
+            Some(TrashEntry { dir, meta })
+        })
+        .collect();
+
+    entries.sort_by_key(|entry| Reverse(entry.meta.deleted_at));
+    entries
+}
+
+/// Moves a project out of the trash back to where it was, along with its tmuxinator config
+///
+/// # Parameters
+///
+/// - `entry` The project to restore
+/// - `tmuxinator_file` Where the project's tmuxinator config should be restored to. An existing
+///   config is kept
+pub fn restore(entry: &TrashEntry, tmuxinator_file: &Path) -> io::Result<()> {
+    if entry.original_path().exists() {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!("{} already exists", entry.original_path().display()),
+        ));
+    }
+
+    if let Some(parent) = entry.original_path().parent() {
+        fs::create_dir_all(parent)?;
+    }
+    move_path(&entry.dir.join(PROJECT_DIR), entry.original_path())?;
+
+    let trashed_tmuxinator_file = entry.dir.join(TMUXINATOR_FILE);
+    if trashed_tmuxinator_file.is_file() && !tmuxinator_file.exists() {
+        if let Some(parent) = tmuxinator_file.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        move_path(&trashed_tmuxinator_file, tmuxinator_file)?;
+    }
+
+    fs::remove_dir_all(&entry.dir)
+}
+
+/// Permanently deletes a project in the trash
+///
+/// # Parameters
+///
+/// - `entry` The project to delete
+pub fn purge(entry: &TrashEntry) -> io::Result<()> {
+    fs::remove_dir_all(&entry.dir)
+}
+
+/// Moves a file or directory, falling back to `mv` when it can't simply be renamed, such as
+/// when the trash is on a different filesystem
+fn move_path(from: &Path, to: &Path) -> io::Result<()> {
+    let err = match fs::rename(from, to) {
+        Ok(()) => return Ok(()),
+        Err(err) => err,
+    };
+
+    match Command::new("mv").arg(from).arg(to).status()?.success() {
+        true => Ok(()),
+        false => Err(err),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::PathBuf};
+
+    use crate::{repo::Repo, state::tags::Tags, test_utils::TempDir};
+
+    use super::{list, move_to_trash, purge, restore, TrashEntry, TrashMeta};
+
+    /// The project trashed by the tests
+    fn workflows() -> Repo {
+        Repo::new("workflows", true, Some("Projects/"))
+    }
+
+    #[test]
+    fn trash_and_restore_works() {
+        let base = TempDir::new("trash");
+        let trash_dir = base.join("trash");
+        let project_root = base.join("Projects/workflows");
+        let tmuxinator_file = base.join("tmuxinator/workflows.yml");
+
+        fs::create_dir_all(&project_root).unwrap();
+        fs::write(project_root.join("main.rs"), "fn main() {}").unwrap();
+        fs::create_dir_all(tmuxinator_file.parent().unwrap()).unwrap();
+        fs::write(&tmuxinator_file, "name: workflows").unwrap();
+
+        let entry =
+            move_to_trash(&trash_dir, &workflows(), &project_root, &tmuxinator_file).unwrap();
+
+        assert!(!project_root.exists());
+        assert!(!tmuxinator_file.exists());
+        assert_eq!(list(&trash_dir), vec![entry.clone()]);
+        assert_eq!(entry.name(), "workflows");
+        assert_eq!(entry.original_path(), project_root);
+        assert!(!entry.expired(30));
+
+        restore(&entry, &tmuxinator_file).unwrap();
+
+        assert_eq!(
+            fs::read_to_string(project_root.join("main.rs")).unwrap(),
+            "fn main() {}"
+        );
+        assert!(tmuxinator_file.exists());
+        assert!(list(&trash_dir).is_empty());
+    }
+
+    #[test]
+    fn expired_works() {
+        let entry = TrashEntry {
+            dir: PathBuf::from("workflows-0"),
+            meta: TrashMeta {
+                name: "workflows".to_string(),
+                original_path: PathBuf::from("/Projects/workflows"),
+                project_dir: None,
+                deleted_at: 0,
+            },
+        };
+
+        assert!(entry.expired(30));
+        assert!(!entry.expired(u64::MAX));
+    }
+
+    #[test]
+    fn tags_survive_trash_and_restore() {
+        let base = TempDir::new("trash-tags");
+        let trash_dir = base.join("trash");
+        let project_root = base.join("Projects/workflows");
+        let tmuxinator_file = base.join("tmuxinator/workflows.yml");
+
+        let mut tags = Tags::default();
+        tags.add_tag(&workflows(), "cli");
+        tags.toggle_favourite(&workflows());
+
+        fs::create_dir_all(&project_root).unwrap();
+        let entry =
+            move_to_trash(&trash_dir, &workflows(), &project_root, &tmuxinator_file).unwrap();
+        restore(&entry, &tmuxinator_file).unwrap();
+
+        let restored = entry.project().unwrap();
+        assert!(tags.has_tag(&restored, "cli"));
+        assert!(tags.is_favourite(&restored));
+    }
+
+    #[test]
+    fn restore_refuses_to_overwrite() {
+        let base = TempDir::new("trash-overwrite");
+        let trash_dir = base.join("trash");
+        let project_root = base.join("Projects/workflows");
+        let tmuxinator_file = base.join("tmuxinator/workflows.yml");
+
+        fs::create_dir_all(&project_root).unwrap();
+        let entry =
+            move_to_trash(&trash_dir, &workflows(), &project_root, &tmuxinator_file).unwrap();
+        fs::create_dir_all(&project_root).unwrap();
+
+        assert!(restore(&entry, &tmuxinator_file).is_err());
+        assert_eq!(list(&trash_dir).len(), 1);
+
+        purge(&entry).unwrap();
+        assert!(list(&trash_dir).is_empty());
+    }
+
+    #[test]
+    fn trashing_twice_keeps_both() {
+        let base = TempDir::new("trash-twice");
+        let trash_dir = base.join("trash");
+        let project_root = base.join("Projects/workflows");
+        let tmuxinator_file = base.join("tmuxinator/workflows.yml");
+
+        for _ in 0..2 {
+            fs::create_dir_all(&project_root).unwrap();
+            move_to_trash(&trash_dir, &workflows(), &project_root, &tmuxinator_file).unwrap();
+        }
+
+        assert_eq!(list(&trash_dir).len(), 2);
+    }
+}
@@ -0,0 +1,116 @@
+//! This module contains helpers shared by the tests, for working in throwaway directories and
+//! git repos
+
+use std::{
+    env, fs,
+    ops::Deref,
+    path::{Path, PathBuf},
+    process::{Command, Output},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+/// Counts the directories created by this process, so every test gets its own
+static NEXT_DIR: AtomicUsize = AtomicUsize::new(0);
+
+/// A directory in the system's temp directory, which is removed when dropped. This means it's
+/// cleaned up even when an assertion in the test fails
+#[derive(Debug)]
+pub struct TempDir {
+    path: PathBuf,
+}
+
+impl TempDir {
+    /// Creates an empty directory, unique to this call
+    ///
+    /// # Parameters
+    ///
+    /// - `name` The name of what's being tested, used to tell the directories apart
+    pub fn new(name: &str) -> Self {
+        let path = env::temp_dir().join(format!(
+            "workflows-{}-{}-{}",
+            name,
+            std::process::id(),
+            NEXT_DIR.fetch_add(1, Ordering::Relaxed)
+        ));
+
+        let _ = fs::remove_dir_all(&path);
+        fs::create_dir_all(&path).unwrap();
+
+        Self { path }
+    }
+}
+
+impl Deref for TempDir {
+    type Target = Path;
+
+    fn deref(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.path);
+    }
+}
+
+/// Runs git in the given directory with a fixed identity, so commits work without a global git
+/// config
+///
+/// # Parameters
+///
+/// - `dir`  The directory to run git in
+/// - `args` The arguments to give git
+pub fn git(dir: &Path, args: &[&str]) -> Output {
+    Command::new("git")
+        .current_dir(dir)
+        .env("LC_ALL", "C")
+        .args([
+            "-c",
+            "user.name=workflows",
+            "-c",
+            "user.email=test@example.com",
+        ])
+        .args(["-c", "commit.gpgsign=false"])
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+/// Initialises an empty git repo on `main`, with an identity set in its config
+///
+/// # Parameters
+///
+/// - `dir` The directory to initialise the repo in
+pub fn init_repo(dir: &Path) {
+    fs::create_dir_all(dir).unwrap();
+
+    git(dir, &["init", "--quiet", "--initial-branch=main"]);
+    set_identity(dir);
+}
+
+/// Sets a git identity in a repo's config, so the code under test can commit to it
+///
+/// # Parameters
+///
+/// - `dir` The root of the repo
+pub fn set_identity(dir: &Path) {
+    for (key, value) in [
+        ("user.name", "workflows"),
+        ("user.email", "test@example.com"),
+        ("commit.gpgsign", "false"),
+    ] {
+        git(dir, &["config", key, value]);
+    }
+}
+
+/// Creates a temp directory with an empty git repo in it
+///
+/// # Parameters
+///
+/// - `name` The name of what's being tested
+pub fn temp_repo(name: &str) -> TempDir {
+    let dir = TempDir::new(name);
+    init_repo(&dir);
+    dir
+}
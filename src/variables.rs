@@ -0,0 +1,368 @@
+//! This module contains the variables made available to templates when a project is created
+//!
+//! Every variable is exposed to a template's commands as an environment variable, prefixed with
+//! `WORKFLOWS_` and in uppercase, e.g. `project_name` becomes `WORKFLOWS_PROJECT_NAME`
+
+use std::{
+    path::Path,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::intergrations;
+
+/// The prefix added to each variable when exposed as an environment variable
+const ENV_PREFIX: &str = "WORKFLOWS_";
+
+/// The variables every template is given, which prompts can't replace
+pub const BUILT_IN_VARIABLES: [&str; 9] = [
+    "project_name",
+    "project_root",
+    "projects_dir",
+    "project_name_kebab",
+    "project_name_snake",
+    "project_name_pascal",
+    "git_user_name",
+    "git_user_email",
+    "date",
+];
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TemplateVariables {
+    /// The variables in the format (name, value)
+    variables: Vec<(String, String)>,
+}
+
+impl TemplateVariables {
+    /// Creates the built in variables for a project
+    ///
+    /// # Parameters
+    ///
+    /// - `project_name` The name of the project being created
+    /// - `projects_dir` The projects directory the project is in, e.g. `Projects/`
+    /// - `project_root` The full path to the project
+    pub fn new(project_name: &str, projects_dir: &str, project_root: &Path) -> Self {
+        let mut variables = Self::default();
+
+        variables.insert("project_name", project_name);
+        variables.insert("project_root", project_root.to_string_lossy());
+        variables.insert("projects_dir", projects_dir);
+        variables.insert("project_name_kebab", kebab_case(project_name));
+        variables.insert("project_name_snake", snake_case(project_name));
+        variables.insert("project_name_pascal", pascal_case(project_name));
+        variables.insert(
+            "git_user_name",
+            intergrations::git::config_value("user.name").unwrap_or_default(),
+        );
+        variables.insert(
+            "git_user_email",
+            intergrations::git::config_value("user.email").unwrap_or_default(),
+        );
+        variables.insert("date", today());
+
+        variables
+    }
+
+    /// Adds a variable, replacing any existing variable with the same name
+    ///
+    /// # Parameters
+    ///
+    /// - `name`  The name of the variable, e.g. `license`
+    /// - `value` The value of the variable
+    pub fn insert(&mut self, name: impl Into<String>, value: impl Into<String>) {
+        let name = snake_case(&name.into());
+        let value = value.into();
+
+        match self.variables.iter_mut().find(|(key, _)| *key == name) {
+            Some((_, existing)) => *existing = value,
+            None => self.variables.push((name, value)),
+        }
+    }
+
+    /// Replaces every `{{name}}` placeholder in the given text with the variable's value.
+    /// Placeholders for unknown variables are left as is.
+    ///
+    /// The text is substituted in a single pass, so placeholders in a variable's value are
+    /// never substituted themselves
+    ///
+    /// # Parameters
+    ///
+    /// - `text` The text to substitute the variables into
+    pub fn substitute(&self, text: &str) -> String {
+        let mut substituted = String::with_capacity(text.len());
+        let mut rest = text;
+
+        while let Some(start) = rest.find("{{") {
+            substituted.push_str(&rest[..start]);
+            rest = &rest[start..];
+
+            let value = rest.find("}}").and_then(|end| {
+                let name = &rest[2..end];
+                self.variables
+                    .iter()
+                    .find(|(key, _)| key == name)
+                    .map(|(_, value)| (value, end + 2))
+            });
+
+            match value {
+                Some((value, placeholder_len)) => {
+                    substituted.push_str(value);
+                    rest = &rest[placeholder_len..];
+                }
+                // Skipping past the braces, as a placeholder could still start inside them
+                None => {
+                    substituted.push_str("{{");
+                    rest = &rest[2..];
+                }
+            }
+        }
+
+        substituted.push_str(rest);
+        substituted
+    }
+
+    /// The variables as environment variables, in the format (key, value)
+    pub fn envs(&self) -> Vec<(String, String)> {
+        self.variables
+            .iter()
+            .map(|(name, value)| (env_name(name), value.to_owned()))
+            .collect()
+    }
+}
+
+/// Gets the environment variable a variable is exposed as
+///
+/// # Parameters
+///
+/// - `name` The name of the variable
+pub fn env_name(name: &str) -> String {
+    format!("{}{}", ENV_PREFIX, snake_case(name).to_uppercase())
+}
+
+/// Splits a name into its lowercase words, breaking on separators and changes in case
+fn words(name: &str) -> Vec<String> {
+    let mut words = vec![];
+    let mut current = String::new();
+    let mut previous_lowercase = false;
+
+    for c in name.chars() {
+        if !c.is_alphanumeric() {
+            if !current.is_empty() {
+                words.push(current.clone());
+                current.clear();
+            }
+            previous_lowercase = false;
+            continue;
+        }
+
+        if c.is_uppercase() && previous_lowercase && !current.is_empty() {
+            words.push(current.clone());
+            current.clear();
+        }
+
+        previous_lowercase = c.is_lowercase() || c.is_numeric();
+        current.extend(c.to_lowercase());
+    }
+
+    if !current.is_empty() {
+        words.push(current);
+    }
+
+    words
+}
+
+/// Converts a name to kebab-case, e.g. `MyProject` becomes `my-project`
+pub fn kebab_case(name: &str) -> String {
+    words(name).join("-")
+}
+
+/// Converts a name to snake_case, e.g. `my-project` becomes `my_project`
+pub fn snake_case(name: &str) -> String {
+    words(name).join("_")
+}
+
+/// Converts a name to PascalCase, e.g. `my-project` becomes `MyProject`
+pub fn pascal_case(name: &str) -> String {
+    words(name)
+        .iter()
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => String::new(),
+            }
+        })
+        .collect()
+}
+
+/// Checks whether a template prompt's name can be used as a variable
+///
+/// # Parameters
+///
+/// - `name` The name of the prompt
+///
+/// # Returns
+///
+/// Why the name can't be used, if it can't
+pub fn validate_prompt_name(name: &str) -> Result<(), String> {
+    let variable = snake_case(name);
+
+    if variable.is_empty() {
+        return Err(format!(
+            "the prompt name {:?} doesn't contain any letters or numbers",
+            name
+        ));
+    }
+
+    if BUILT_IN_VARIABLES.contains(&variable.as_str()) {
+        return Err(format!(
+            "the prompt {} has the same name as a built in variable",
+            name
+        ));
+    }
+
+    Ok(())
+}
+
+/// Gets today's date in UTC, in the format `YYYY-MM-DD`
+fn today() -> String {
+    let seconds = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+
+    let (year, month, day) = civil_from_days((seconds / 86400) as i64);
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+/// Converts a number of days since 1970-01-01 to a (year, month, day) date, using Howard
+/// Hinnant's `civil_from_days` algorithm
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let days = days + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days.rem_euclid(146097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+
+    let day = (day_of_year - (153 * month_index + 2) / 5 + 1) as u32;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    } as u32;
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::{
+        civil_from_days, env_name, kebab_case, pascal_case, snake_case, validate_prompt_name,
+        TemplateVariables, BUILT_IN_VARIABLES,
+    };
+
+    #[test]
+    fn kebab_case_works() {
+        assert_eq!(kebab_case("my_project"), "my-project");
+        assert_eq!(kebab_case("MyProject"), "my-project");
+        assert_eq!(kebab_case("my project2"), "my-project2");
+    }
+
+    #[test]
+    fn snake_case_works() {
+        assert_eq!(snake_case("my-project"), "my_project");
+        assert_eq!(snake_case("myProject"), "my_project");
+        assert_eq!(snake_case("my project"), "my_project");
+    }
+
+    #[test]
+    fn pascal_case_works() {
+        assert_eq!(pascal_case("my-project"), "MyProject");
+        assert_eq!(pascal_case("my_cool_project"), "MyCoolProject");
+        assert_eq!(pascal_case("workflows"), "Workflows");
+    }
+
+    #[test]
+    fn env_name_works() {
+        assert_eq!(env_name("project_name"), "WORKFLOWS_PROJECT_NAME");
+        assert_eq!(env_name("license-type"), "WORKFLOWS_LICENSE_TYPE");
+    }
+
+    #[test]
+    fn built_in_variables_works() {
+        let variables = TemplateVariables::new("workflows", "Projects/", Path::new("/workflows"));
+
+        let names: Vec<String> = variables.envs().into_iter().map(|(name, _)| name).collect();
+
+        assert_eq!(
+            names,
+            BUILT_IN_VARIABLES
+                .iter()
+                .map(|name| env_name(name))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn validate_prompt_name_works() {
+        assert!(validate_prompt_name("license").is_ok());
+        assert!(validate_prompt_name("Project-Name").is_err());
+        assert!(validate_prompt_name("date").is_err());
+        assert!(validate_prompt_name("").is_err());
+        assert!(validate_prompt_name("--").is_err());
+    }
+
+    #[test]
+    fn civil_from_days_works() {
+        assert_eq!(civil_from_days(0), (1970, 1, 1));
+        assert_eq!(civil_from_days(59), (1970, 3, 1));
+        assert_eq!(civil_from_days(11016), (2000, 2, 29));
+        assert_eq!(civil_from_days(20745), (2026, 10, 19));
+        assert_eq!(civil_from_days(-1), (1969, 12, 31));
+    }
+
+    #[test]
+    fn insert_replaces_existing_variables() {
+        let mut variables = TemplateVariables::default();
+
+        variables.insert("license", "MIT");
+        variables.insert("license", "GPL-3.0");
+
+        assert_eq!(
+            variables.envs(),
+            vec![("WORKFLOWS_LICENSE".to_string(), "GPL-3.0".to_string())]
+        );
+    }
+
+    #[test]
+    fn substitute_works() {
+        let mut variables = TemplateVariables::default();
+
+        variables.insert("project_name", "workflows");
+        variables.insert("license", "MIT");
+
+        assert_eq!(
+            variables.substitute("# {{project_name}}\n\nLicensed under {{license}} {{unknown}}"),
+            "# workflows\n\nLicensed under MIT {{unknown}}"
+        );
+        assert_eq!(variables.substitute("{{{{license}}}}"), "{{MIT}}");
+        assert_eq!(variables.substitute("{{license"), "{{license");
+    }
+
+    #[test]
+    fn substitute_is_a_single_pass() {
+        let mut variables = TemplateVariables::default();
+
+        variables.insert("description", "Uses {{license}}");
+        variables.insert("license", "MIT");
+
+        assert_eq!(
+            variables.substitute("{{description}}, {{license}}"),
+            "Uses {{license}}, MIT"
+        );
+    }
+}